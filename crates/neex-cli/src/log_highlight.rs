@@ -0,0 +1,84 @@
+//! Log line highlighting for the TUI's main panel
+//!
+//! `draw_main` used to colorize task logs with three hardcoded substring
+//! checks (`contains("error")` and friends), which missed structured output
+//! entirely and mangled any line that already carried its own ANSI color
+//! codes (a compiler or linter printing its own colored output). A log line
+//! either already has ANSI codes - parsed with `ansi-to-tui` so those codes
+//! render as intended instead of leaking through as literal escape bytes -
+//! or is plain text, in which case `syntect` tokenizes it against a loaded
+//! syntax set so JSON payloads, stack-trace frames, and compiler diagnostics
+//! get real highlighting instead of three keyword checks.
+
+use ansi_to_tui::IntoText;
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SynStyle, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static SET: OnceLock<ThemeSet> = OnceLock::new();
+    SET.get_or_init(ThemeSet::load_defaults)
+}
+
+/// Render one captured task log line as styled `ratatui` lines, picking
+/// whichever of the two highlighting paths fits: ANSI passthrough for lines
+/// a subprocess already colored, `syntect` tokenizing for plain ones.
+pub fn highlight_log_line(log: &str) -> Vec<Line<'static>> {
+    if log.contains('\u{1b}') {
+        if let Ok(text) = log.as_bytes().to_vec().into_text() {
+            return text.lines;
+        }
+        // Malformed escape sequence - fall through and render as plain text.
+    }
+
+    let syntax = pick_syntax(log);
+    let theme = &theme_set().themes["base16-ocean.dark"];
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    match highlighter.highlight_line(log, syntax_set()) {
+        Ok(ranges) => vec![Line::from(
+            ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    ratatui::text::Span::styled(text.to_string(), to_ratatui_style(style))
+                })
+                .collect::<Vec<_>>(),
+        )],
+        Err(_) => vec![Line::from(log.to_string())],
+    }
+}
+
+/// Guess a syntax definition from the shape of the line rather than a file
+/// extension, since a log line doesn't have one: JSON payloads and
+/// `file:line` stack-trace frames each have a recognizable shape.
+fn pick_syntax(log: &str) -> &'static SyntaxReference {
+    let set = syntax_set();
+    let trimmed = log.trim_start();
+
+    let name = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        "JSON"
+    } else if trimmed.starts_with("at ") || log.contains(".rs:") || log.contains(".ts:") {
+        "Rust" // closest built-in grammar for file:line stack frames
+    } else {
+        "Plain Text"
+    };
+
+    set.find_syntax_by_name(name)
+        .unwrap_or_else(|| set.find_syntax_plain_text())
+}
+
+fn to_ratatui_style(style: SynStyle) -> Style {
+    Style::default().fg(Color::Rgb(
+        style.foreground.r,
+        style.foreground.g,
+        style.foreground.b,
+    ))
+}
@@ -9,16 +9,18 @@
 //!   neex --graph            # Show dependency graph
 //!   neex --login            # Setup cloud cache
 
+mod log_highlight;
 mod tui;
 
 use anyhow::Result;
 use clap::Parser;
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Password, Select};
 use neex_core::{
-    hash_ast, is_parseable, load_config, save_config, CloudCache, CloudConfig, DepGraph, Hasher,
-    S3Config, Scheduler, SchedulerTask, SymbolCache, SymbolGraph, TaskRunner,
+    hash_ast, is_parseable, load_config, save_config, plan_resume, CloudCache, CloudConfig,
+    DepGraph, Hasher, JobState, ResumeAction, S3Config, Scheduler, SchedulerTask, SymbolCache,
+    SymbolGraph, TaskRunner, WorkerReporter,
 };
-use neex_daemon::{DaemonRequest, DaemonResponse};
+use neex_daemon::{DaemonRequest, DaemonResponse, JobStore, WorkerState};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
@@ -48,14 +50,33 @@ struct Cli {
     #[arg(long)]
     changed: bool,
 
+    /// Base ref to diff against for `--changed` (merge-base is used, not the
+    /// ref's tip, so commits that landed on the base after this branch forked
+    /// aren't flagged)
+    #[arg(long, default_value = "origin/main")]
+    base: String,
+
     /// Use symbol-level tracking (smart rebuild)
     #[arg(long)]
     symbols: bool,
 
+    /// Watch for changes and rerun only the affected packages
+    #[arg(long, short = 'w')]
+    watch: bool,
+
     /// Concurrency limit
     #[arg(long, short = 'c')]
     concurrency: Option<usize>,
 
+    /// Cache TTL in seconds — a cached result older than this is a miss
+    #[arg(long)]
+    ttl: Option<u64>,
+
+    /// Serve cached output immediately but refresh in the background once
+    /// it's older than this many seconds (works with or without --ttl)
+    #[arg(long)]
+    stale_after: Option<u64>,
+
     // ═══════════════════════════════════════
     // Special Commands (Flags)
     // ═══════════════════════════════════════
@@ -108,6 +129,22 @@ struct Cli {
     /// Stop daemon
     #[arg(long)]
     daemon_stop: bool,
+
+    /// List the daemon's background workers (cloud uploads, long builds, etc.)
+    #[arg(long)]
+    workers: bool,
+
+    /// Pause a worker by id (use with --workers)
+    #[arg(long)]
+    pause: Option<u64>,
+
+    /// Resume a paused worker by id (use with --workers)
+    #[arg(long)]
+    resume: Option<u64>,
+
+    /// Cancel a worker by id (use with --workers)
+    #[arg(long)]
+    cancel: Option<u64>,
 }
 
 fn get_socket_path() -> PathBuf {
@@ -117,6 +154,17 @@ fn get_socket_path() -> PathBuf {
         .join("daemon.sock")
 }
 
+/// Best-effort read of the daemon's persisted tranquility level, so a
+/// `Scheduler` run started from the CLI throttles itself the same way a
+/// `WorkerPool` driven by the daemon would. No daemon running (or any other
+/// error) just means no throttling, same as before tranquility existed.
+async fn current_tranquility() -> u8 {
+    match send_request(&get_socket_path(), DaemonRequest::GetTranquility).await {
+        Ok(DaemonResponse::Tranquility(level)) => level,
+        _ => 0,
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -169,26 +217,87 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    if cli.workers {
+        return show_workers(cli.pause, cli.resume, cli.cancel).await;
+    }
+
     // ═══════════════════════════════════════
     // Task Execution
     // ═══════════════════════════════════════
 
-    let Some(task) = cli.task else {
+    let Some(task) = cli.task.clone() else {
         print_usage();
         return Ok(());
     };
 
-    // Run task with flags
-    if cli.symbols {
-        run_symbols(&cwd, &task).await
+    let policy = neex_core::CachePolicy {
+        ttl: cli.ttl.map(std::time::Duration::from_secs),
+        stale_after: cli.stale_after.map(std::time::Duration::from_secs),
+    };
+
+    let aliases = neex_core::load_task_aliases(&cwd)?;
+    let stages = if aliases.is_empty() {
+        vec![task.clone()]
+    } else {
+        if let Ok(graph) = DepGraph::from_root(&cwd) {
+            neex_core::check_alias_collisions(&aliases, &graph.packages())?;
+        }
+        neex_core::resolve_alias(&aliases, &task)?
+    };
+
+    if stages.len() > 1 {
+        println!("▶ pipeline '{}': {}", task, stages.join(" → "));
+    }
+
+    let webhook = neex_core::WebhookEmitter::try_from_config()?;
+
+    // The interactive TUI (`tui::run_tui` / `tui::TuiReporter`) isn't wired
+    // up behind a CLI flag yet, so only the non-interactive side of the
+    // `Reporter` split is reachable today - a `CiReporter` when stdout isn't
+    // a TTY, otherwise the existing plain `println!`s in `run_task`/`run_all`.
+    let reporter: Option<Arc<dyn neex_core::Reporter>> = if neex_core::CiReporter::should_use() {
+        Some(Arc::new(neex_core::CiReporter::new()))
+    } else {
+        None
+    };
+
+    let result = async {
+        for stage in &stages {
+            dispatch_task(&cwd, stage, &cli, policy, webhook.as_ref(), reporter.clone()).await?;
+        }
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+
+    if let Some(w) = &webhook {
+        w.shutdown().await;
+    }
+
+    result
+}
+
+/// Run a single (already alias-resolved) task name honoring `--watch`,
+/// `--symbols`, `--all`, `--changed`, and `--filter`, in that priority order.
+async fn dispatch_task(
+    cwd: &PathBuf,
+    task: &str,
+    cli: &Cli,
+    policy: neex_core::CachePolicy,
+    webhook: Option<&neex_core::WebhookEmitter>,
+    reporter: Option<Arc<dyn neex_core::Reporter>>,
+) -> Result<()> {
+    if cli.watch {
+        run_watch(cwd, task).await
+    } else if cli.symbols {
+        run_symbols(cwd, task).await
     } else if cli.all {
-        run_all(&cwd, &task, cli.concurrency).await
+        run_all(cwd, task, cli.concurrency, policy, webhook, reporter).await
     } else if cli.changed {
-        run_changed(&cwd, &task, cli.concurrency).await
-    } else if let Some(pkg) = cli.filter {
-        run_filtered(&cwd, &task, &pkg).await
+        run_changed(cwd, task, &cli.base, cli.concurrency, policy).await
+    } else if let Some(pkg) = &cli.filter {
+        run_filtered(cwd, task, pkg, policy).await
     } else {
-        run_task(&cwd, &task).await
+        run_task(cwd, task, policy, webhook, reporter).await
     }
 }
 
@@ -199,6 +308,7 @@ fn print_usage() {
     println!("  neex <task>              Run a task (build, dev, test, etc.)");
     println!("  neex <task> --all        Run on all packages");
     println!("  neex <task> --filter=pkg Run on specific package");
+    println!("  neex <task> --watch      Rerun on change, affected packages only");
     println!();
     println!("COMMANDS:");
     println!("  --graph      Show dependency graph");
@@ -206,6 +316,7 @@ fn print_usage() {
     println!("  --info       Project info");
     println!("  --login      Setup cloud cache");
     println!("  --prune      Clean cache");
+    println!("  --workers    List daemon background workers");
     println!();
     println!("EXAMPLES:");
     println!("  neex build");
@@ -217,7 +328,13 @@ fn print_usage() {
 // Task Execution
 // ═══════════════════════════════════════
 
-async fn run_task(cwd: &PathBuf, task: &str) -> Result<()> {
+async fn run_task(
+    cwd: &PathBuf,
+    task: &str,
+    policy: neex_core::CachePolicy,
+    webhook: Option<&neex_core::WebhookEmitter>,
+    reporter: Option<Arc<dyn neex_core::Reporter>>,
+) -> Result<()> {
     let start = Instant::now();
     let runner = TaskRunner::new(cwd)?;
 
@@ -230,15 +347,35 @@ async fn run_task(cwd: &PathBuf, task: &str) -> Result<()> {
     };
 
     print!("▶ {}", task);
+    if let Some(w) = webhook {
+        w.emit(neex_core::WebhookEvent::task_started(task));
+    }
+    if let Some(r) = &reporter {
+        r.on_task_start(task);
+    }
 
     let hasher = Hasher::new(cwd);
     let hash = hasher.global_hash()?;
     let key = format!("{}:{}", task, &hash[..16]);
 
     // L1: Local
-    if let Some(cached) = runner.get_cached(&key)? {
-        println!(" ⚡ {}ms", start.elapsed().as_millis());
-        runner.replay_output(&cached);
+    if let Some(cached) = runner.get_cached_fresh(&key, &command, policy).await? {
+        runner.restore_outputs(&cached).await?;
+        let duration = start.elapsed();
+        println!(" ⚡ {}ms", duration.as_millis());
+        runner.replay_output(&cached, None);
+        if let Some(w) = webhook {
+            w.emit(neex_core::WebhookEvent::cache_hit(task, "local"));
+            w.emit(neex_core::WebhookEvent::task_finished(
+                task,
+                "completed",
+                duration.as_millis() as u64,
+                Some("local"),
+            ));
+        }
+        if let Some(r) = &reporter {
+            r.on_task_finish(&task_result(task, neex_core::TaskStatus::Completed, duration, true, None));
+        }
         return Ok(());
     }
 
@@ -246,32 +383,96 @@ async fn run_task(cwd: &PathBuf, task: &str) -> Result<()> {
     if let Ok(Some(cloud)) = CloudCache::try_new() {
         if let Ok(Some(data)) = cloud.download(&key).await {
             if let Ok(output) = serde_json::from_slice::<neex_core::TaskOutput>(&data) {
-                let _ = runner.store_cached(&key, &output);
+                let _ = runner.store_cached(&key, &output).await;
+                runner.restore_outputs(&output).await?;
+                let duration = start.elapsed();
                 println!(" ☁️ cloud");
-                runner.replay_output(&output);
+                runner.replay_output(&output, None);
+                if let Some(w) = webhook {
+                    w.emit(neex_core::WebhookEvent::cache_hit(task, "cloud"));
+                    w.emit(neex_core::WebhookEvent::task_finished(
+                        task,
+                        "completed",
+                        duration.as_millis() as u64,
+                        Some("cloud"),
+                    ));
+                }
+                if let Some(r) = &reporter {
+                    r.on_task_finish(&task_result(task, neex_core::TaskStatus::Completed, duration, true, None));
+                }
                 return Ok(());
             }
         }
     }
 
     // L4: Execute
+    if let Some(w) = webhook {
+        w.emit(neex_core::WebhookEvent::cache_miss(task));
+    }
     println!();
-    let output = runner.execute(&command).await?;
+    let output = runner.execute(&command, None).await?;
 
     let mut out = output.clone();
     out.hash = key.clone();
-    runner.store_cached(&key, &out)?;
+    let output_globs = runner.get_outputs(task)?;
+    if !output_globs.is_empty() {
+        out.outputs = Some(runner.capture_outputs(&output_globs).await?);
+    }
+    runner.store_cached(&key, &out).await?;
 
     // Background upload
     if let Ok(json) = serde_json::to_vec(&out) {
-        CloudCache::upload_background(key, json);
+        let reporter: Option<Arc<dyn WorkerReporter>> = Some(Arc::new(SocketWorkerReporter {
+            socket: get_socket_path(),
+        }));
+        CloudCache::upload_background(key, json, reporter);
     }
 
-    println!("✓ {} {}ms", task, start.elapsed().as_millis());
+    let duration = start.elapsed();
+    println!("✓ {} {}ms", task, duration.as_millis());
+    let status = if out.exit_code == 0 {
+        neex_core::TaskStatus::Completed
+    } else {
+        neex_core::TaskStatus::Failed
+    };
+    if let Some(w) = webhook {
+        w.emit(neex_core::WebhookEvent::task_finished(
+            task,
+            if out.exit_code == 0 { "completed" } else { "failed" },
+            duration.as_millis() as u64,
+            None,
+        ));
+    }
+    if let Some(r) = &reporter {
+        let error = (out.exit_code != 0).then(|| format!("exit code {}", out.exit_code));
+        r.on_task_finish(&task_result(task, status, duration, false, error));
+    }
     Ok(())
 }
 
-/// Smart rebuild using symbol-level tracking
+/// Build a `neex_core::TaskResult` for a `Reporter` callback out of a
+/// `run_task` invocation, which (unlike `Scheduler::execute`) doesn't
+/// already produce one.
+fn task_result(
+    name: &str,
+    status: neex_core::TaskStatus,
+    duration: std::time::Duration,
+    cached: bool,
+    error: Option<String>,
+) -> neex_core::TaskResult {
+    neex_core::TaskResult {
+        name: name.to_string(),
+        status,
+        duration,
+        error,
+        cached,
+        attempts: 1,
+    }
+}
+
+/// Smart rebuild using symbol-level tracking: only packages that own a file
+/// consuming a changed export get rebuilt, not every package that imports the
+/// changed file wholesale.
 async fn run_symbols(cwd: &PathBuf, task: &str) -> Result<()> {
     let start = Instant::now();
 
@@ -279,27 +480,25 @@ async fn run_symbols(cwd: &PathBuf, task: &str) -> Result<()> {
     println!("  Building symbol graph...");
 
     // Build symbol graph
-    let graph = match SymbolGraph::build(cwd) {
+    let graph = match SymbolGraph::build_from_root(cwd) {
         Ok(g) => g,
         Err(e) => {
             println!("⚠️ Symbol graph failed: {}", e);
             println!("  Falling back to normal build...");
-            return run_all(cwd, task, None).await;
+            return run_all(cwd, task, None, neex_core::CachePolicy::default(), None, None).await;
         }
     };
 
-    let (pkgs, symbols, consumers) = graph.stats();
+    let (files, symbols, links) = graph.stats();
     println!(
-        "  📦 {} packages, 🔣 {} symbols, 🔗 {} links",
-        pkgs, symbols, consumers
+        "  📦 {} files, 🔣 {} symbols, 🔗 {} links",
+        files, symbols, links
     );
 
-    // Load previous cache
+    // Compare against the last run's persisted symbol hashes
     let cache_path = cwd.join(".neex").join("symbols.json");
     let old_cache = SymbolCache::load(&cache_path).unwrap_or_default();
-
-    // Find changed symbols
-    let changed = graph.get_changed_symbols(&old_cache);
+    let changed = graph.changed_since(&old_cache);
 
     if changed.is_empty() {
         println!();
@@ -312,13 +511,11 @@ async fn run_symbols(cwd: &PathBuf, task: &str) -> Result<()> {
 
     println!("  ⚠️ {} symbols changed", changed.len());
 
-    // Get affected files
-    let affected = graph.get_affected_files(&changed);
+    let affected = graph.rebuild_order(&changed);
 
     if affected.is_empty() {
         println!("  No consumers affected");
 
-        // Still save new cache
         let _ = graph.to_cache().save(&cache_path);
 
         println!();
@@ -336,17 +533,15 @@ async fn run_symbols(cwd: &PathBuf, task: &str) -> Result<()> {
         println!("    → {}", name);
     }
 
-    // Run task for affected packages
+    // Run task for the packages owning each affected file, in dependency order
     let dep_graph = DepGraph::from_root(cwd)?;
-    let mut rebuilt = 0;
+    let mut rebuilt_packages = std::collections::HashSet::new();
 
     for file in &affected {
-        // Find which package this file belongs to
         for pkg in dep_graph.packages() {
             let pkg_path = cwd.join(&pkg.path);
-            if file.starts_with(&pkg_path) {
-                run_task(&pkg_path, task).await?;
-                rebuilt += 1;
+            if file.starts_with(&pkg_path) && rebuilt_packages.insert(pkg_path.clone()) {
+                run_task(&pkg_path, task, neex_core::CachePolicy::default(), None, None).await?;
                 break;
             }
         }
@@ -358,13 +553,20 @@ async fn run_symbols(cwd: &PathBuf, task: &str) -> Result<()> {
     println!();
     println!(
         "✓ {} packages rebuilt ({} ms)",
-        rebuilt,
+        rebuilt_packages.len(),
         start.elapsed().as_millis()
     );
     Ok(())
 }
 
-async fn run_all(cwd: &PathBuf, task: &str, concurrency: Option<usize>) -> Result<()> {
+async fn run_all(
+    cwd: &PathBuf,
+    task: &str,
+    concurrency: Option<usize>,
+    policy: neex_core::CachePolicy,
+    webhook: Option<&neex_core::WebhookEmitter>,
+    reporter: Option<Arc<dyn neex_core::Reporter>>,
+) -> Result<()> {
     let start = Instant::now();
     let graph = DepGraph::from_root(cwd)?;
 
@@ -375,8 +577,22 @@ async fn run_all(cwd: &PathBuf, task: &str, concurrency: Option<usize>) -> Resul
 
     println!("▶ {} --all ({} packages)", task, graph.package_count());
 
+    let job_store = open_job_store(cwd)?;
+    let current_hash = Arc::new(Hasher::new(cwd).global_hash()?);
+    let resume = Arc::new(load_resume_plan(&job_store, &current_hash)?);
+
     let order = graph.get_build_order()?;
-    let tasks = create_tasks(cwd, &order, task, &graph);
+    let tasks = create_tasks(
+        cwd,
+        &order,
+        task,
+        &graph,
+        policy,
+        reporter.clone(),
+        job_store,
+        current_hash,
+        resume,
+    );
 
     let c = concurrency.unwrap_or_else(|| {
         std::thread::available_parallelism()
@@ -384,49 +600,343 @@ async fn run_all(cwd: &PathBuf, task: &str, concurrency: Option<usize>) -> Resul
             .unwrap_or(4)
     });
 
-    let results = Scheduler::new(c).execute(tasks).await?;
+    let results = Scheduler::new(c)
+        .with_tranquility(current_tranquility().await)
+        .execute(tasks)
+        .await?;
 
     let ok = results
         .iter()
         .filter(|r| r.status == neex_core::TaskStatus::Completed)
         .count();
+    let cached = results.iter().filter(|r| r.cached).count();
     let fail = results
         .iter()
         .filter(|r| r.status == neex_core::TaskStatus::Failed)
         .count();
 
+    if let Some(w) = webhook {
+        w.emit(neex_core::WebhookEvent::run_completed(
+            ok,
+            fail,
+            start.elapsed().as_millis() as u64,
+        ));
+    }
+
+    if let Some(r) = &reporter {
+        for result in &results {
+            r.on_task_finish(result);
+        }
+        r.on_run_summary(&neex_core::RunSummary {
+            ran: ok,
+            cached,
+            failed: fail,
+            total_duration: start.elapsed(),
+        });
+    }
+
     if fail == 0 {
-        println!("✓ {} packages {}ms", ok, start.elapsed().as_millis());
+        println!(
+            "✓ {} packages ({} cached) {}ms",
+            ok,
+            cached,
+            start.elapsed().as_millis()
+        );
     } else {
         println!("✗ {}ok {}fail {}ms", ok, fail, start.elapsed().as_millis());
     }
     Ok(())
 }
 
-async fn run_changed(cwd: &PathBuf, task: &str, concurrency: Option<usize>) -> Result<()> {
-    println!("▶ {} --changed (TODO: git integration)", task);
-    run_all(cwd, task, concurrency).await
+/// Find changed files against `base` (merge-base, not its tip, plus any
+/// uncommitted work), map each to its owning package by longest matching
+/// `node.path` prefix, and run only those packages plus their dependents, in
+/// build order. Mirrors the affected-graph model moon uses for task running.
+async fn run_changed(
+    cwd: &PathBuf,
+    task: &str,
+    base: &str,
+    concurrency: Option<usize>,
+    policy: neex_core::CachePolicy,
+) -> Result<()> {
+    let start = Instant::now();
+    let graph = DepGraph::from_root(cwd)?;
+
+    if graph.package_count() == 0 {
+        println!("❌ No packages");
+        return Ok(());
+    }
+
+    let changed_files = match changed_files_since(cwd, base) {
+        Ok(files) => files,
+        Err(e) => {
+            println!("⚠️ git diff against '{}' failed: {}", base, e);
+            println!("  Falling back to full run...");
+            return run_all(cwd, task, concurrency, policy, None, None).await;
+        }
+    };
+
+    if changed_files.is_empty() {
+        println!("⚡ No changes vs {} ({} ms)", base, start.elapsed().as_millis());
+        return Ok(());
+    }
+
+    let mut direct = std::collections::HashSet::new();
+    for file in &changed_files {
+        if let Some(name) = owning_package(cwd, file, &graph) {
+            direct.insert(name);
+        }
+    }
+
+    if direct.is_empty() {
+        println!("⚡ {} file(s) changed, none owned by a package", changed_files.len());
+        return Ok(());
+    }
+
+    let mut affected_names = std::collections::HashSet::new();
+    for name in &direct {
+        for node in graph.get_affected(name) {
+            affected_names.insert(node.name.clone());
+        }
+    }
+
+    println!(
+        "▶ {} --changed vs {} ({} changed, {} affected)",
+        task,
+        base,
+        direct.len(),
+        affected_names.len()
+    );
+
+    let order: Vec<&neex_core::WorkspaceNode> = graph
+        .get_build_order()?
+        .into_iter()
+        .filter(|node| affected_names.contains(&node.name))
+        .collect();
+
+    let job_store = open_job_store(cwd)?;
+    let current_hash = Arc::new(Hasher::new(cwd).global_hash()?);
+    let resume = Arc::new(load_resume_plan(&job_store, &current_hash)?);
+
+    let tasks = create_tasks(
+        cwd, &order, task, &graph, policy, None, job_store, current_hash, resume,
+    );
+
+    let c = concurrency.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|p| p.get())
+            .unwrap_or(4)
+    });
+
+    let results = Scheduler::new(c)
+        .with_tranquility(current_tranquility().await)
+        .execute(tasks)
+        .await?;
+
+    let ok = results
+        .iter()
+        .filter(|r| r.status == neex_core::TaskStatus::Completed)
+        .count();
+    let cached = results.iter().filter(|r| r.cached).count();
+    let fail = results
+        .iter()
+        .filter(|r| r.status == neex_core::TaskStatus::Failed)
+        .count();
+
+    if fail == 0 {
+        println!(
+            "✓ {} packages ({} cached) {}ms",
+            ok,
+            cached,
+            start.elapsed().as_millis()
+        );
+    } else {
+        println!("✗ {}ok {}fail {}ms", ok, fail, start.elapsed().as_millis());
+    }
+    Ok(())
 }
 
-async fn run_filtered(cwd: &PathBuf, task: &str, pkg: &str) -> Result<()> {
+/// Files that differ from `base` (via merge-base, so the rest of that
+/// branch's own unmerged history doesn't count as "changed") plus whatever
+/// is uncommitted right now.
+fn changed_files_since(cwd: &Path, base: &str) -> Result<Vec<PathBuf>> {
+    let merge_base = run_git(cwd, &["merge-base", base, "HEAD"])?;
+    let merge_base = merge_base.trim();
+
+    let diff_range = format!("{}...HEAD", merge_base);
+    let committed = run_git(cwd, &["diff", "--name-only", &diff_range])?;
+    let status = run_git(cwd, &["status", "--porcelain"])?;
+
+    let mut files = std::collections::HashSet::new();
+    for line in committed.lines() {
+        let line = line.trim();
+        if !line.is_empty() {
+            files.insert(cwd.join(line));
+        }
+    }
+    for line in status.lines() {
+        // `git status --porcelain` prefixes each line with a two-char status
+        // code (e.g. " M", "??") before the path.
+        if let Some(path) = line.get(3..) {
+            if !path.is_empty() {
+                files.insert(cwd.join(path));
+            }
+        }
+    }
+
+    Ok(files.into_iter().collect())
+}
+
+fn run_git(cwd: &Path, args: &[&str]) -> Result<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The package whose workspace directory is the longest matching prefix of
+/// `file`, i.e. the package that owns it.
+fn owning_package(cwd: &Path, file: &Path, graph: &DepGraph) -> Option<String> {
+    graph
+        .packages()
+        .into_iter()
+        .filter(|pkg| file.starts_with(cwd.join(&pkg.path)))
+        .max_by_key(|pkg| pkg.path.as_os_str().len())
+        .map(|pkg| pkg.name.clone())
+}
+
+/// Run `task` once, then keep watching the tree, rerunning only the packages
+/// whose files (or something they import) changed. Builds used by nothing that
+/// changed stay on their cached output.
+async fn run_watch(cwd: &PathBuf, task: &str) -> Result<()> {
+    run_task(cwd, task, neex_core::CachePolicy::default(), None, None).await?;
+    println!("👀 watching for changes ({})", task);
+
+    let mut watcher = neex_daemon::FileWatcher::new(cwd)?;
+    watcher.start()?;
+
+    let graph = DepGraph::from_root(cwd)?;
+    for pkg in graph.packages() {
+        watcher.register_package_root(&cwd.join(&pkg.path));
+    }
+
+    let cwd = cwd.clone();
+    let task = task.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        watcher.watch_loop(std::time::Duration::from_millis(300), |batch| {
+            let changed: Vec<PathBuf> = batch.into_iter().map(|c| c.path).collect();
+            println!("\n🔄 {} file(s) changed", changed.len());
+
+            let import_graph = match neex_core::ImportGraph::build(&cwd) {
+                Ok(g) => g,
+                Err(e) => {
+                    eprintln!("⚠ failed to rebuild import graph: {}", e);
+                    return;
+                }
+            };
+
+            let packages = graph.packages();
+            let affected = import_graph.affected_packages(&changed, &packages);
+
+            if affected.is_empty() {
+                println!("  (no packages affected)");
+                return;
+            }
+
+            let handle = tokio::runtime::Handle::current();
+            for name in affected {
+                let Some(pkg) = graph.get_package(name) else {
+                    continue;
+                };
+                let pkg_path = cwd.join(&pkg.path);
+                let task = task.clone();
+                handle.block_on(async {
+                    if let Err(e) = run_task(&pkg_path, &task, neex_core::CachePolicy::default(), None, None).await {
+                        eprintln!("✗ {}: {}", name, e);
+                    }
+                });
+            }
+        });
+    })
+    .await?;
+
+    Ok(())
+}
+
+async fn run_filtered(
+    cwd: &PathBuf,
+    task: &str,
+    pkg: &str,
+    policy: neex_core::CachePolicy,
+) -> Result<()> {
     let graph = DepGraph::from_root(cwd)?;
 
     if let Some(p) = graph.get_package(pkg) {
         println!("▶ {} --filter={}", task, pkg);
         let path = cwd.join(&p.path);
-        run_task(&path, task).await
+        run_task(&path, task, policy, None, None).await
     } else {
         println!("❌ Package '{}' not found", pkg);
         Ok(())
     }
 }
 
+/// Open (or create) this workspace's local job store for resumable builds.
+/// A separate sled DB from the daemon's `.neex/daemon.db` - sled locks its
+/// file to one process, and a daemon may already be running against its own.
+fn open_job_store(cwd: &Path) -> Result<JobStore> {
+    let db_path = cwd.join(".neex").join("jobs.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db = sled::open(&db_path)?;
+    JobStore::new(&db)
+}
+
+/// Decide a [`ResumeAction`] for every persisted job against the workspace's
+/// current global hash, drop the ones `plan_resume` says are stale, and
+/// return the rest keyed by task name so `create_tasks` can skip a
+/// `Skip`-planned task outright (a `Restart`-planned one just runs as usual).
+fn load_resume_plan(
+    store: &JobStore,
+    current_hash: &str,
+) -> Result<std::collections::HashMap<String, ResumeAction>> {
+    let mut actions = std::collections::HashMap::new();
+    for entry in plan_resume(store.load_all()?, current_hash) {
+        if entry.action == ResumeAction::Discard {
+            store.remove(&entry.job.task_name)?;
+        } else {
+            actions.insert(entry.job.task_name, entry.action);
+        }
+    }
+    Ok(actions)
+}
+
 fn create_tasks(
     cwd: &Path,
     order: &[&neex_core::WorkspaceNode],
     task: &str,
     graph: &DepGraph,
+    policy: neex_core::CachePolicy,
+    reporter: Option<Arc<dyn neex_core::Reporter>>,
+    job_store: JobStore,
+    current_hash: Arc<String>,
+    resume: Arc<std::collections::HashMap<String, ResumeAction>>,
 ) -> Vec<SchedulerTask> {
+    // `dependsOn: ["^<task>"]` is the only pipeline shape we support today: a
+    // package's task implicitly depends on the same task in its workspace
+    // dependencies, so "build" always runs upstream "build"s first.
     let mut deps: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
 
     for node in graph.packages() {
@@ -457,38 +967,100 @@ fn create_tasks(
             let d = deps.get(&name).cloned().unwrap_or_default();
             let r = Arc::clone(&root);
             let t = Arc::clone(&task_arc);
+            let reporter = reporter.clone();
+            let job_store = job_store.clone();
+            let hash = Arc::clone(&current_hash);
+            let resume = Arc::clone(&resume);
 
             SchedulerTask::new(name.clone(), d, move || {
-                let full = r.join(&path);
-                let pkg_path = full.join("package.json");
-                let content = std::fs::read_to_string(&pkg_path)?;
-                let pkg: serde_json::Value = serde_json::from_str(&content)?;
-
-                if let Some(cmd) = pkg
-                    .get("scripts")
-                    .and_then(|s| s.get(t.as_str()))
-                    .and_then(|c| c.as_str())
-                {
-                    print!("  {} ", name);
-                    let out = std::process::Command::new("sh")
-                        .arg("-c")
-                        .arg(cmd)
-                        .current_dir(&full)
-                        .output()?;
-
-                    if out.status.success() {
-                        println!("✓");
-                    } else {
-                        println!("✗");
-                        return Err(anyhow::anyhow!("failed"));
+                let r = Arc::clone(&r);
+                let t = Arc::clone(&t);
+                let name = name.clone();
+                let path = path.clone();
+                let reporter = reporter.clone();
+                let job_store = job_store.clone();
+                let hash = Arc::clone(&hash);
+                let resume = Arc::clone(&resume);
+                async move {
+                    if matches!(resume.get(&name), Some(ResumeAction::Skip)) {
+                        return Ok(true);
                     }
+
+                    let mut job = JobState::new(name.clone(), hash.as_str());
+                    job.status = neex_core::TaskStatus::Running;
+                    let _ = job_store.save(&job);
+
+                    let pkg_path = r.join(&path);
+                    let result = run_package_task(&pkg_path, &name, &t, policy, reporter).await;
+
+                    job.status = match &result {
+                        Ok(_) => neex_core::TaskStatus::Completed,
+                        Err(_) => neex_core::TaskStatus::Failed,
+                    };
+                    let _ = job_store.save(&job);
+
+                    result
                 }
-                Ok(())
             })
         })
         .collect()
 }
 
+/// Run `task` inside `pkg_path` on behalf of the scheduler: consults the task
+/// cache by the package's `Hasher::global_hash` before spawning anything, and
+/// prefixes every streamed line with `label` so concurrent package output
+/// stays readable. That key hashes file contents, not the AST, so an edit that
+/// only touches comments or whitespace is still a cache miss.
+/// Returns `Ok(true)` on a cache hit or when the package has no `task`
+/// script, `Ok(false)` if it actually executed.
+async fn run_package_task(
+    pkg_path: &Path,
+    label: &str,
+    task: &str,
+    policy: neex_core::CachePolicy,
+    reporter: Option<Arc<dyn neex_core::Reporter>>,
+) -> Result<bool> {
+    let runner = TaskRunner::new(pkg_path)?;
+
+    let Some(command) = runner.get_script(task)? else {
+        return Ok(true);
+    };
+
+    if let Some(r) = &reporter {
+        r.on_task_start(label);
+    }
+
+    let hasher = Hasher::new(pkg_path);
+    let hash = hasher.global_hash()?;
+    let key = format!("{}:{}", task, &hash[..16]);
+
+    if let Some(cached) = runner.get_cached_fresh(&key, &command, policy).await? {
+        runner.restore_outputs(&cached).await?;
+        println!("  {} ⚡ cached", label);
+        runner.replay_output(&cached, Some(label));
+        return Ok(true);
+    }
+
+    println!("  {} ▶", label);
+    let output = runner.execute(&command, Some(label)).await?;
+
+    let mut out = output.clone();
+    out.hash = key.clone();
+    let output_globs = runner.get_outputs(task)?;
+    if !output_globs.is_empty() {
+        out.outputs = Some(runner.capture_outputs(&output_globs).await?);
+    }
+    runner.store_cached(&key, &out).await?;
+
+    if out.exit_code != 0 {
+        println!("  {} ✗", label);
+        return Err(anyhow::anyhow!("{} failed", label));
+    }
+
+    println!("  {} ✓", label);
+    Ok(false)
+}
+
 // ═══════════════════════════════════════
 // Special Commands
 // ═══════════════════════════════════════
@@ -666,11 +1238,55 @@ async fn prune_cache(cwd: &PathBuf, all: bool) -> Result<()> {
         }
     }
 
-    TaskRunner::new(cwd)?.clear_cache()?;
+    TaskRunner::new(cwd)?.clear_cache().await?;
     println!("✅ Cache cleared");
     Ok(())
 }
 
+/// `neex workers` and its `--pause`/`--resume`/`--cancel <id>` control flags:
+/// sends the matching `DaemonRequest` over the Unix socket, then (for
+/// control flags) re-lists so the new state is visible immediately.
+async fn show_workers(pause: Option<u64>, resume: Option<u64>, cancel: Option<u64>) -> Result<()> {
+    let socket = get_socket_path();
+
+    if let Some(id) = pause {
+        send_request(&socket, DaemonRequest::PauseWorker { id }).await?;
+    }
+    if let Some(id) = resume {
+        send_request(&socket, DaemonRequest::ResumeWorker { id }).await?;
+    }
+    if let Some(id) = cancel {
+        send_request(&socket, DaemonRequest::CancelWorker { id }).await?;
+    }
+
+    match send_request(&socket, DaemonRequest::ListWorkers).await? {
+        DaemonResponse::Workers(workers) if workers.is_empty() => {
+            println!("No background workers");
+        }
+        DaemonResponse::Workers(workers) => {
+            for w in workers {
+                let state = match &w.state {
+                    WorkerState::Active => "active".to_string(),
+                    WorkerState::Idle => "idle".to_string(),
+                    WorkerState::Dead(reason) => format!("dead: {}", reason),
+                };
+                println!(
+                    "#{} {} [{}] {} (errors: {})",
+                    w.id,
+                    w.name,
+                    state,
+                    w.progress.as_deref().unwrap_or("-"),
+                    w.error_count
+                );
+            }
+        }
+        DaemonResponse::Error(e) => println!("❌ {}", e),
+        _ => println!("❌ unexpected daemon response"),
+    }
+
+    Ok(())
+}
+
 // ═══════════════════════════════════════
 // Helpers
 // ═══════════════════════════════════════
@@ -711,3 +1327,29 @@ async fn send_request(socket: &PathBuf, req: DaemonRequest) -> Result<DaemonResp
 async fn send_request(_socket: &PathBuf, _req: DaemonRequest) -> Result<DaemonResponse> {
     anyhow::bail!("Daemon mode is not supported on Windows")
 }
+
+/// [`WorkerReporter`] over the daemon's Unix socket, so a job the CLI kicks
+/// off (it runs here, not in the daemon process) still shows up in
+/// `neex workers` via `DaemonRequest::RegisterWorker`/`ReportWorker`.
+struct SocketWorkerReporter {
+    socket: PathBuf,
+}
+
+#[async_trait::async_trait]
+impl WorkerReporter for SocketWorkerReporter {
+    async fn register(&self, name: String) -> Result<u64> {
+        match send_request(&self.socket, DaemonRequest::RegisterWorker { name }).await? {
+            DaemonResponse::WorkerId(id) => Ok(id),
+            DaemonResponse::Error(e) => anyhow::bail!(e),
+            _ => anyhow::bail!("unexpected response registering worker"),
+        }
+    }
+
+    async fn report(&self, id: u64, state: WorkerState, progress: Option<String>) -> Result<()> {
+        match send_request(&self.socket, DaemonRequest::ReportWorker { id, state, progress }).await? {
+            DaemonResponse::Ok => Ok(()),
+            DaemonResponse::Error(e) => anyhow::bail!(e),
+            _ => anyhow::bail!("unexpected response reporting worker"),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use neex_daemon::{DaemonRequest, DaemonResponse};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -20,7 +21,9 @@ use ratatui::{
     widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
-use std::io;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use sysinfo::System;
@@ -30,6 +33,9 @@ use sysinfo::System;
 pub enum TaskStatus {
     Pending,
     Running,
+    /// Suspended mid-run (shutdown signal or explicit pause) with a resume
+    /// plan persisted in the daemon's job store
+    Paused,
     Completed(u64), // ms
     Failed(String),
     Cached(u64),    // ms
@@ -41,6 +47,12 @@ pub struct TuiTask {
     pub name: String,
     pub status: TaskStatus,
     pub logs: Vec<String>,
+    /// Index of the topmost visible log line, within the lines that pass the
+    /// active severity filter. Ignored while `follow_tail` is set.
+    pub scroll: usize,
+    /// Auto-scroll to the newest log line as it arrives. Cleared as soon as
+    /// the user scrolls manually, and restored with `End`/`G`.
+    pub follow_tail: bool,
 }
 
 impl TuiTask {
@@ -49,10 +61,77 @@ impl TuiTask {
             name: name.to_string(),
             status: TaskStatus::Pending,
             logs: Vec::new(),
+            scroll: 0,
+            follow_tail: true,
         }
     }
 }
 
+/// Background worker snapshot for the TUI's worker overlay - a copy of
+/// `neex_daemon::WorkerInfo` with its state flattened to display text
+/// ("active", "idle", or a dead worker's reason).
+#[derive(Clone, Debug)]
+pub struct TuiWorker {
+    pub id: u64,
+    pub name: String,
+    pub state: String,
+    pub progress: Option<String>,
+    pub error_count: u32,
+}
+
+/// How strictly the main log panel filters by line severity, cycled with `f`.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum SeverityFilter {
+    #[default]
+    All,
+    WarnAndAbove,
+    ErrorOnly,
+}
+
+impl SeverityFilter {
+    fn allows(self, line: &str) -> bool {
+        match self {
+            SeverityFilter::All => true,
+            SeverityFilter::WarnAndAbove => line_severity(line) != LineSeverity::Info,
+            SeverityFilter::ErrorOnly => line_severity(line) == LineSeverity::Error,
+        }
+    }
+
+    fn cycle(self) -> Self {
+        match self {
+            SeverityFilter::All => SeverityFilter::WarnAndAbove,
+            SeverityFilter::WarnAndAbove => SeverityFilter::ErrorOnly,
+            SeverityFilter::ErrorOnly => SeverityFilter::All,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SeverityFilter::All => "all",
+            SeverityFilter::WarnAndAbove => "warn+",
+            SeverityFilter::ErrorOnly => "error",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum LineSeverity {
+    Error,
+    Warn,
+    Info,
+}
+
+fn line_severity(line: &str) -> LineSeverity {
+    let lower = line.to_ascii_lowercase();
+    if lower.contains("error") {
+        LineSeverity::Error
+    } else if lower.contains("warn") {
+        LineSeverity::Warn
+    } else {
+        LineSeverity::Info
+    }
+}
+
 /// TUI State
 pub struct TuiState {
     pub tasks: Vec<TuiTask>,
@@ -65,6 +144,25 @@ pub struct TuiState {
     pub should_quit: bool,
     pub p2p_peers: usize,
     pub cloud_enabled: bool,
+    /// Severity filter applied to the main log panel, cycled with `f`.
+    pub severity_filter: SeverityFilter,
+    /// Whether `/` search is currently capturing keystrokes into `search_query`.
+    pub search_active: bool,
+    pub search_query: String,
+    /// Indices (within the selected task's filtered lines) of lines matching
+    /// `search_query`, recomputed on every query edit or filter change.
+    pub search_matches: Vec<usize>,
+    /// Position within `search_matches` of the match the view is currently
+    /// scrolled to (shown as the top line of the log panel).
+    pub search_cursor: usize,
+    /// Latest snapshot of the daemon's background workers, for the header
+    /// count and the `w`-togglable overlay.
+    pub workers: Vec<TuiWorker>,
+    /// Whether the worker overlay is currently shown, toggled with `w`.
+    pub show_workers: bool,
+    /// 0-10: how much the worker pool should throttle itself, adjusted with
+    /// `+`/`-` and persisted by the daemon across restarts.
+    pub tranquility: u8,
 }
 
 impl Default for TuiState {
@@ -80,6 +178,14 @@ impl Default for TuiState {
             should_quit: false,
             p2p_peers: 0,
             cloud_enabled: false,
+            severity_filter: SeverityFilter::default(),
+            search_active: false,
+            search_query: String::new(),
+            search_matches: Vec::new(),
+            search_cursor: 0,
+            workers: Vec::new(),
+            show_workers: false,
+            tranquility: 0,
         }
     }
 }
@@ -126,10 +232,237 @@ impl TuiState {
             self.selected = self.selected.checked_sub(1).unwrap_or(self.tasks.len() - 1);
         }
     }
+
+    /// Selected task's log lines that pass the current severity filter - the
+    /// coordinate space `scroll` and `search_matches` are expressed in.
+    pub fn visible_lines(&self) -> Vec<&str> {
+        match self.tasks.get(self.selected) {
+            Some(task) => task
+                .logs
+                .iter()
+                .filter(|l| self.severity_filter.allows(l))
+                .map(|l| l.as_str())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        if let Some(task) = self.tasks.get_mut(self.selected) {
+            task.follow_tail = false;
+            task.scroll = task.scroll.saturating_sub(amount);
+        }
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        let len = self.visible_lines().len();
+        if let Some(task) = self.tasks.get_mut(self.selected) {
+            task.follow_tail = false;
+            task.scroll = (task.scroll + amount).min(len);
+        }
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        if let Some(task) = self.tasks.get_mut(self.selected) {
+            task.follow_tail = false;
+            task.scroll = 0;
+        }
+    }
+
+    pub fn scroll_to_tail(&mut self) {
+        if let Some(task) = self.tasks.get_mut(self.selected) {
+            task.follow_tail = true;
+        }
+    }
+
+    pub fn cycle_severity_filter(&mut self) {
+        self.severity_filter = self.severity_filter.cycle();
+        self.recompute_search_matches();
+    }
+
+    /// Enter `/` search mode: further key presses are captured into
+    /// `search_query` until `confirm_search` or `cancel_search`.
+    pub fn start_search(&mut self) {
+        self.search_active = true;
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.search_cursor = 0;
+    }
+
+    pub fn push_search_char(&mut self, c: char) {
+        self.search_query.push(c);
+        self.recompute_search_matches();
+    }
+
+    pub fn pop_search_char(&mut self) {
+        self.search_query.pop();
+        self.recompute_search_matches();
+    }
+
+    /// Stop capturing keystrokes and jump to the first match, if any.
+    pub fn confirm_search(&mut self) {
+        self.search_active = false;
+        self.jump_to_match();
+    }
+
+    /// Abandon the in-progress search, clearing any highlighted matches.
+    pub fn cancel_search(&mut self) {
+        self.search_active = false;
+        self.search_query.clear();
+        self.search_matches.clear();
+    }
+
+    pub fn next_match(&mut self) {
+        if self.search_matches.is_empty() {
+            return;
+        }
+        self.search_cursor = (self.search_cursor + 1) % self.search_matches.len();
+        self.jump_to_match();
+    }
+
+    pub fn prev_match(&mut self) {
+        if self.search_matches.is_empty() {
+            return;
+        }
+        self.search_cursor = self
+            .search_cursor
+            .checked_sub(1)
+            .unwrap_or(self.search_matches.len() - 1);
+        self.jump_to_match();
+    }
+
+    fn recompute_search_matches(&mut self) {
+        self.search_cursor = 0;
+        if self.search_query.is_empty() {
+            self.search_matches.clear();
+            return;
+        }
+        let query = self.search_query.to_ascii_lowercase();
+        self.search_matches = self
+            .visible_lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.to_ascii_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect();
+    }
+
+    /// Replace the worker snapshot shown in the header and overlay, e.g.
+    /// after polling `DaemonRequest::ListWorkers`.
+    pub fn set_workers(&mut self, workers: Vec<TuiWorker>) {
+        self.workers = workers;
+    }
+
+    pub fn toggle_workers_overlay(&mut self) {
+        self.show_workers = !self.show_workers;
+    }
+
+    /// Adjust the tranquility level by `delta`, clamped to 0-10. The caller
+    /// is responsible for forwarding the new value to the daemon via
+    /// `DaemonRequest::SetTranquility` so it's actually applied and persisted.
+    pub fn adjust_tranquility(&mut self, delta: i8) {
+        let current = self.tranquility as i8;
+        self.tranquility = (current + delta).clamp(0, 10) as u8;
+    }
+
+    fn jump_to_match(&mut self) {
+        if let Some(&line) = self.search_matches.get(self.search_cursor) {
+            if let Some(task) = self.tasks.get_mut(self.selected) {
+                task.follow_tail = false;
+                task.scroll = line;
+            }
+        }
+    }
+}
+
+/// `neex_core::Reporter` that drives a shared `TuiState` from scheduler
+/// callbacks, so `run_tui`'s render loop and the task execution that feeds it
+/// stay decoupled - a `CiReporter` can stand in for this one with no changes
+/// on the execution side. Locks only for the duration of each update, never
+/// across an `.await`, so the render loop is never blocked longer than that.
+pub struct TuiReporter {
+    state: Arc<Mutex<TuiState>>,
+}
+
+impl TuiReporter {
+    pub fn new(state: Arc<Mutex<TuiState>>) -> Self {
+        Self { state }
+    }
+}
+
+impl neex_core::Reporter for TuiReporter {
+    fn on_task_start(&self, task: &str) {
+        let mut state = self.state.lock().unwrap();
+        if !state.tasks.iter().any(|t| t.name == task) {
+            state.add_task(task);
+        }
+        state.update_task(task, TaskStatus::Running);
+    }
+
+    fn on_task_log(&self, task: &str, line: &str, _is_stderr: bool) {
+        self.state.lock().unwrap().add_log(task, line);
+    }
+
+    fn on_task_finish(&self, result: &neex_core::TaskResult) {
+        let ms = result.duration.as_millis() as u64;
+        let status = match result.status {
+            neex_core::TaskStatus::Completed if result.cached => TaskStatus::Cached(ms),
+            neex_core::TaskStatus::Completed => TaskStatus::Completed(ms),
+            neex_core::TaskStatus::Failed => {
+                TaskStatus::Failed(result.error.clone().unwrap_or_default())
+            }
+            neex_core::TaskStatus::Cancelled => TaskStatus::Failed("cancelled".to_string()),
+            neex_core::TaskStatus::Pending | neex_core::TaskStatus::Running | neex_core::TaskStatus::Paused => {
+                return;
+            }
+        };
+        self.state.lock().unwrap().update_task(&result.name, status);
+    }
+}
+
+/// Blocking round-trip to the daemon over its Unix socket, using the same
+/// newline-delimited JSON protocol `neex_daemon::server` and the CLI's async
+/// `send_request` speak. Blocking rather than async because the TUI's
+/// crossterm event loop below is synchronous and doesn't run on a runtime.
+fn send_daemon_request(socket: &Path, req: &DaemonRequest) -> Result<DaemonResponse> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.write_all(serde_json::to_string(req)?.as_bytes())?;
+    stream.write_all(b"\n")?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Ask the daemon for its current worker list and store it in `state`.
+/// Errors (daemon not running, etc.) are swallowed - the overlay just shows
+/// whatever was there before, same as any other best-effort poll.
+fn refresh_workers(state: &Arc<Mutex<TuiState>>, socket: &Path) {
+    if let Ok(DaemonResponse::Workers(workers)) =
+        send_daemon_request(socket, &DaemonRequest::ListWorkers)
+    {
+        let workers = workers
+            .into_iter()
+            .map(|w| TuiWorker {
+                id: w.id,
+                name: w.name,
+                state: match w.state {
+                    neex_daemon::WorkerState::Active => "active".to_string(),
+                    neex_daemon::WorkerState::Idle => "idle".to_string(),
+                    neex_daemon::WorkerState::Dead(reason) => reason,
+                },
+                progress: w.progress,
+                error_count: w.error_count,
+            })
+            .collect();
+        state.lock().unwrap().set_workers(workers);
+    }
 }
 
 /// Run TUI application
-pub fn run_tui(state: Arc<Mutex<TuiState>>) -> Result<()> {
+pub fn run_tui(state: Arc<Mutex<TuiState>>, socket: std::path::PathBuf) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -164,17 +497,63 @@ pub fn run_tui(state: Arc<Mutex<TuiState>>) -> Result<()> {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     let mut state_guard = state.lock().unwrap();
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => {
-                            state_guard.should_quit = true;
-                        }
-                        KeyCode::Tab | KeyCode::Down | KeyCode::Char('j') => {
-                            state_guard.next();
+
+                    if state_guard.search_active {
+                        match key.code {
+                            KeyCode::Enter => state_guard.confirm_search(),
+                            KeyCode::Esc => state_guard.cancel_search(),
+                            KeyCode::Backspace => state_guard.pop_search_char(),
+                            KeyCode::Char(c) => state_guard.push_search_char(c),
+                            _ => {}
                         }
-                        KeyCode::BackTab | KeyCode::Up | KeyCode::Char('k') => {
-                            state_guard.prev();
+                    } else {
+                        let page = terminal
+                            .size()
+                            .map(|s| (s.height as usize / 2).max(1))
+                            .unwrap_or(10);
+
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => {
+                                state_guard.should_quit = true;
+                            }
+                            KeyCode::Tab | KeyCode::Down | KeyCode::Char('j') => {
+                                state_guard.next();
+                            }
+                            KeyCode::BackTab | KeyCode::Up | KeyCode::Char('k') => {
+                                state_guard.prev();
+                            }
+                            KeyCode::PageUp => state_guard.scroll_up(page),
+                            KeyCode::PageDown => state_guard.scroll_down(page),
+                            KeyCode::Home | KeyCode::Char('g') => state_guard.scroll_to_top(),
+                            KeyCode::End | KeyCode::Char('G') => state_guard.scroll_to_tail(),
+                            KeyCode::Char('/') => state_guard.start_search(),
+                            KeyCode::Char('n') => state_guard.next_match(),
+                            KeyCode::Char('N') => state_guard.prev_match(),
+                            KeyCode::Char('f') => state_guard.cycle_severity_filter(),
+                            KeyCode::Char('w') => {
+                                state_guard.toggle_workers_overlay();
+                                let showing = state_guard.show_workers;
+                                drop(state_guard);
+                                if showing {
+                                    refresh_workers(&state, &socket);
+                                }
+                            }
+                            KeyCode::Char('+') | KeyCode::Char('=') => {
+                                state_guard.adjust_tranquility(1);
+                                let level = state_guard.tranquility;
+                                drop(state_guard);
+                                let _ =
+                                    send_daemon_request(&socket, &DaemonRequest::SetTranquility { level });
+                            }
+                            KeyCode::Char('-') => {
+                                state_guard.adjust_tranquility(-1);
+                                let level = state_guard.tranquility;
+                                drop(state_guard);
+                                let _ =
+                                    send_daemon_request(&socket, &DaemonRequest::SetTranquility { level });
+                            }
+                            _ => {}
                         }
-                        _ => {}
                     }
                 }
             }
@@ -227,6 +606,70 @@ fn ui(f: &mut Frame, state: &TuiState, cpu: f32, mem: u64) {
 
     // Footer
     draw_footer(f, chunks[2]);
+
+    // Worker overlay, toggled with `w` - drawn last so it sits on top
+    if state.show_workers {
+        draw_workers_overlay(f, size, state);
+    }
+}
+
+/// Centered popup rect occupying `percent_x`/`percent_y` of `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Popup listing every background worker's id, name, state, last reported
+/// progress message and error count, toggled with `w`. Only the number of
+/// errors is shown, not the error messages themselves.
+fn draw_workers_overlay(f: &mut Frame, area: Rect, state: &TuiState) {
+    let popup_area = centered_rect(60, 50, area);
+
+    let items: Vec<ListItem> = if state.workers.is_empty() {
+        vec![ListItem::new("No background workers")]
+    } else {
+        state
+            .workers
+            .iter()
+            .map(|w| {
+                let style = match w.state.as_str() {
+                    "active" => Style::default().fg(Color::Yellow),
+                    "idle" => Style::default().fg(Color::Gray),
+                    _ => Style::default().fg(Color::Red),
+                };
+                let text = format!(
+                    "#{} {} [{}] {} (errors: {})",
+                    w.id,
+                    w.name,
+                    w.state,
+                    w.progress.as_deref().unwrap_or("-"),
+                    w.error_count
+                );
+                ListItem::new(text).style(style)
+            })
+            .collect()
+    };
+
+    let title = format!("⚙ Workers (tranquility {}/10) [w to close]", state.tranquility);
+    let list = List::new(items).block(Block::default().title(title).borders(Borders::ALL));
+
+    f.render_widget(ratatui::widgets::Clear, popup_area);
+    f.render_widget(list, popup_area);
 }
 
 /// Draw header with logo, status, progress
@@ -253,7 +696,11 @@ fn draw_header(f: &mut Frame, area: Rect, state: &TuiState, cpu: f32, mem: u64)
         "P2P:Off".to_string()
     };
     let cloud = if state.cloud_enabled { "☁️ On" } else { "☁️ Off" };
-    let status_text = format!(" {} │ {} │ CPU:{}% │ {}MB", p2p, cloud, cpu as u32, mem);
+    let active_workers = state.workers.iter().filter(|w| w.state == "active").count();
+    let status_text = format!(
+        " {} │ {} │ CPU:{}% │ {}MB │ W:{} │ Tranq:{}",
+        p2p, cloud, cpu as u32, mem, active_workers, state.tranquility
+    );
     let status = Paragraph::new(status_text)
         .style(Style::default().fg(Color::Gray))
         .block(Block::default().borders(Borders::ALL));
@@ -284,6 +731,7 @@ fn draw_sidebar(f: &mut Frame, area: Rect, state: &TuiState) {
             let (icon, style) = match &task.status {
                 TaskStatus::Pending => ("⏸", Style::default().fg(Color::DarkGray)),
                 TaskStatus::Running => ("⏳", Style::default().fg(Color::Yellow)),
+                TaskStatus::Paused => ("⏯", Style::default().fg(Color::Magenta)),
                 TaskStatus::Completed(ms) => {
                     let text = format!("✓ {} {}ms", task.name, ms);
                     return ListItem::new(text).style(Style::default().fg(Color::Green));
@@ -326,24 +774,51 @@ fn draw_sidebar(f: &mut Frame, area: Rect, state: &TuiState) {
 /// Draw main log panel
 fn draw_main(f: &mut Frame, area: Rect, state: &TuiState) {
     let selected_task = state.tasks.get(state.selected);
-    
+    let viewport = area.height.saturating_sub(2) as usize;
+
     let (title, logs) = match selected_task {
         Some(task) => {
-            let title = format!("📋 {}", task.name);
-            let logs: Vec<Line> = task.logs.iter().map(|log| {
-                // Syntax highlighting
-                let style = if log.contains("error") || log.contains("Error") || log.contains("ERROR") {
-                    Style::default().fg(Color::Red)
-                } else if log.contains("warn") || log.contains("Warn") || log.contains("WARN") {
-                    Style::default().fg(Color::Yellow)
-                } else if log.contains("✓") || log.contains("success") || log.contains("Success") {
-                    Style::default().fg(Color::Green)
-                } else {
-                    Style::default()
-                };
-                
-                Line::from(Span::styled(log.clone(), style))
-            }).collect();
+            let visible = state.visible_lines();
+            let max_start = visible.len().saturating_sub(viewport);
+            let start = if task.follow_tail {
+                max_start
+            } else {
+                task.scroll.min(max_start)
+            };
+
+            let logs: Vec<Line> = visible[start..]
+                .iter()
+                .enumerate()
+                .flat_map(|(i, log)| {
+                    let mut rendered = crate::log_highlight::highlight_log_line(log);
+                    if state.search_matches.contains(&(start + i)) {
+                        for line in &mut rendered {
+                            *line = std::mem::take(line)
+                                .style(Style::default().bg(Color::Yellow).fg(Color::Black));
+                        }
+                    }
+                    rendered
+                })
+                .collect();
+
+            let filter_suffix = match state.severity_filter {
+                SeverityFilter::All => String::new(),
+                other => format!(" [{}]", other.label()),
+            };
+            let search_suffix = if state.search_active {
+                format!(" │ /{}", state.search_query)
+            } else if !state.search_query.is_empty() {
+                format!(
+                    " │ /{} ({}/{})",
+                    state.search_query,
+                    state.search_matches.len().min(state.search_cursor + 1),
+                    state.search_matches.len()
+                )
+            } else {
+                String::new()
+            };
+
+            let title = format!("📋 {}{}{}", task.name, filter_suffix, search_suffix);
             (title, logs)
         }
         None => ("📋 No task selected".to_string(), vec![]),
@@ -363,6 +838,18 @@ fn draw_footer(f: &mut Frame, area: Rect) {
         Span::raw(" Switch "),
         Span::styled("[↑↓]", Style::default().fg(Color::Cyan)),
         Span::raw(" Navigate "),
+        Span::styled("[PgUp/PgDn/g/G]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Scroll "),
+        Span::styled("[/]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Search "),
+        Span::styled("[n/N]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Next/Prev "),
+        Span::styled("[f]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Filter "),
+        Span::styled("[w]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Workers "),
+        Span::styled("[+/-]", Style::default().fg(Color::Cyan)),
+        Span::raw(" Tranquility "),
         Span::styled("[q]", Style::default().fg(Color::Cyan)),
         Span::raw(" Quit "),
     ]);
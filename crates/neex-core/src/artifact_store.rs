@@ -0,0 +1,167 @@
+//! Content-Addressable Artifact Store
+//!
+//! `CacheEntry`/`TaskOutput` used to record stdout/stderr and nothing else, so a
+//! cache hit replayed logs but never restored the actual build outputs
+//! (`dist/`, `.next/`, etc). This module hashes each declared output file with
+//! BLAKE3 and stores the bytes once through a `CacheBackend`'s blob variant
+//! (deduplicated across tasks and packages, and shared with a remote cache
+//! when one is configured), recording a manifest of relative path -> blob
+//! mapping that can be replayed on a cache hit to materialize the real files.
+
+use crate::cache_backend::CacheBackend;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A single file captured as part of a task's outputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobEntry {
+    /// BLAKE3 hash of the file content (hex)
+    pub hash: String,
+    /// Unix file mode bits, best-effort on non-unix platforms
+    pub mode: u32,
+}
+
+/// Relative path -> blob mapping for everything a task produced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub files: HashMap<PathBuf, BlobEntry>,
+}
+
+/// Content-addressable blob store, backed by a pluggable `CacheBackend`
+#[derive(Clone)]
+pub struct ArtifactStore {
+    backend: Arc<dyn CacheBackend>,
+}
+
+impl ArtifactStore {
+    pub fn new(backend: Arc<dyn CacheBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Store a single file's bytes, deduplicating by content hash. Returns the
+    /// blob entry describing what was stored.
+    pub async fn put_file(&self, path: &Path) -> Result<BlobEntry> {
+        let content = fs::read(path)?;
+        let hash = blake3::hash(&content).to_hex().to_string();
+        let mode = file_mode(path);
+
+        self.backend.put_blob(&hash, content).await?;
+
+        Ok(BlobEntry { hash, mode })
+    }
+
+    /// Capture every file matched by `patterns` (relative glob patterns rooted
+    /// at `pkg_root`) into the blob store and return the resulting manifest.
+    pub async fn capture(&self, pkg_root: &Path, patterns: &[String]) -> Result<Manifest> {
+        let mut manifest = Manifest::default();
+
+        for pattern in patterns {
+            let full_pattern = pkg_root.join(pattern).to_string_lossy().to_string();
+            for entry in glob::glob(&full_pattern)? {
+                let path = entry?;
+                if !path.is_file() {
+                    continue;
+                }
+                let rel = path.strip_prefix(pkg_root).unwrap_or(&path).to_path_buf();
+                let blob = self.put_file(&path).await?;
+                manifest.files.insert(rel, blob);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Materialize every file in `manifest` under `pkg_root` from the blob store
+    pub async fn restore(&self, pkg_root: &Path, manifest: &Manifest) -> Result<()> {
+        for (rel, blob) in &manifest.files {
+            let dest = pkg_root.join(rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let data = self
+                .backend
+                .get_blob(&blob.hash)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("missing blob {} for {:?}", blob.hash, rel))?;
+
+            fs::write(&dest, data)?;
+            set_file_mode(&dest, blob.mode);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn file_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.permissions().mode())
+        .unwrap_or(0o644)
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> u32 {
+    0o644
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = fs::set_permissions(path, fs::Permissions::from_mode(mode));
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache_backend::LocalBackend;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_capture_and_restore_roundtrip() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let pkg = root.join("pkg");
+        fs::create_dir_all(pkg.join("dist")).unwrap();
+        fs::write(pkg.join("dist/out.js"), b"console.log(1)").unwrap();
+
+        let backend = Arc::new(LocalBackend::new(root).unwrap());
+        let store = ArtifactStore::new(backend);
+        let manifest = store
+            .capture(&pkg, &["dist/*.js".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(manifest.files.len(), 1);
+
+        fs::remove_file(pkg.join("dist/out.js")).unwrap();
+        store.restore(&pkg, &manifest).await.unwrap();
+
+        let restored = fs::read(pkg.join("dist/out.js")).unwrap();
+        assert_eq!(restored, b"console.log(1)");
+    }
+
+    #[tokio::test]
+    async fn test_dedup_same_content_same_hash() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        fs::write(root.join("a.txt"), b"same content").unwrap();
+        fs::write(root.join("b.txt"), b"same content").unwrap();
+
+        let backend = Arc::new(LocalBackend::new(root).unwrap());
+        let store = ArtifactStore::new(backend);
+        let a = store.put_file(&root.join("a.txt")).await.unwrap();
+        let b = store.put_file(&root.join("b.txt")).await.unwrap();
+
+        assert_eq!(a.hash, b.hash);
+    }
+}
@@ -0,0 +1,326 @@
+//! Pluggable Cache Backend - share cached outputs between developers and CI
+//!
+//! `TaskRunner` used to hardcode a local `sled::Db`, so a cache built on one
+//! machine was invisible everywhere else. `CacheBackend` abstracts storage
+//! behind `get`/`put`/`has` plus a blob variant for the artifact CAS, so a
+//! `LocalBackend` and a remote HTTP/S3 backend can be swapped in from a single
+//! `address` string, and `TieredBackend` layers the two together.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Storage for cached task outputs (`TaskOutput` blobs) and artifact blobs
+/// (the content-addressable files an output manifest points at).
+#[async_trait::async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>>;
+    async fn put(&self, hash: &str, data: Vec<u8>) -> Result<()>;
+    async fn has(&self, hash: &str) -> Result<bool> {
+        Ok(self.get(hash).await?.is_some())
+    }
+
+    async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>>;
+    async fn put_blob(&self, hash: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Drop everything. Local-only: remote backends are shared, so this is a no-op there.
+    async fn clear(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Best-effort (entry count, bytes) size accounting, surfaced through
+    /// `TaskRunner::cache_stats`; remote backends report zero.
+    async fn stats(&self) -> Result<(usize, u64)> {
+        Ok((0, 0))
+    }
+}
+
+/// Local sled-backed cache: the default, single-machine store
+pub struct LocalBackend {
+    db: sled::Db,
+    blob_dir: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let cache_dir = root.as_ref().join(".neex").join("cache");
+        std::fs::create_dir_all(&cache_dir)?;
+        let db = sled::open(&cache_dir)?;
+
+        let blob_dir = cache_dir.join("cas");
+        std::fs::create_dir_all(&blob_dir)?;
+
+        Ok(Self { db, blob_dir })
+    }
+
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        let prefix = &hash[..2.min(hash.len())];
+        self.blob_dir.join(prefix).join(hash)
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for LocalBackend {
+    async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(hash.as_bytes())?.map(|v| v.to_vec()))
+    }
+
+    async fn put(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.db.insert(hash.as_bytes(), data)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    async fn has(&self, hash: &str) -> Result<bool> {
+        Ok(self.db.contains_key(hash.as_bytes())?)
+    }
+
+    async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.blob_path(hash);
+        if path.exists() {
+            Ok(Some(std::fs::read(path)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn put_blob(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.blob_path(hash);
+        if path.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.db.clear()?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<(usize, u64)> {
+        Ok((self.db.len(), self.db.size_on_disk()?))
+    }
+}
+
+/// HTTP(S) remote cache speaking a plain REST contract:
+/// `GET/PUT /outputs/<hash>` and `GET/PUT /blobs/<hash>`.
+pub struct HttpBackend {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn fetch(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), path);
+        let resp = self.client.get(&url).send().await?;
+
+        if resp.status().is_success() {
+            Ok(Some(resp.bytes().await?.to_vec()))
+        } else if resp.status().as_u16() == 404 {
+            Ok(None)
+        } else {
+            Err(anyhow!("remote cache GET {} failed: {}", url, resp.status()))
+        }
+    }
+
+    async fn store(&self, path: &str, data: Vec<u8>) -> Result<()> {
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), path);
+        let resp = self.client.put(&url).body(data).send().await?;
+
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("remote cache PUT {} failed: {}", url, resp.status()))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for HttpBackend {
+    async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        self.fetch(&format!("outputs/{}", hash)).await
+    }
+
+    async fn put(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.store(&format!("outputs/{}", hash), data).await
+    }
+
+    async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        self.fetch(&format!("blobs/{}", hash)).await
+    }
+
+    async fn put_blob(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.store(&format!("blobs/{}", hash), data).await
+    }
+}
+
+/// S3/R2 remote cache, reusing the existing `CloudCache` client
+pub struct S3Backend {
+    cloud: crate::cloud::CloudCache,
+}
+
+impl S3Backend {
+    pub fn from_config(config: &crate::cloud::S3Config) -> Result<Self> {
+        Ok(Self {
+            cloud: crate::cloud::CloudCache::from_config(config)?,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for S3Backend {
+    async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        self.cloud.download(&format!("outputs/{}", hash)).await
+    }
+
+    async fn put(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.cloud.upload(&format!("outputs/{}", hash), data).await
+    }
+
+    async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        self.cloud.download(&format!("blobs/{}", hash)).await
+    }
+
+    async fn put_blob(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.cloud.upload(&format!("blobs/{}", hash), data).await
+    }
+}
+
+/// Checks local first, falls back to remote on miss, writes through to both on store
+pub struct TieredBackend {
+    local: Arc<dyn CacheBackend>,
+    remote: Arc<dyn CacheBackend>,
+}
+
+impl TieredBackend {
+    pub fn new(local: Arc<dyn CacheBackend>, remote: Arc<dyn CacheBackend>) -> Self {
+        Self { local, remote }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for TieredBackend {
+    async fn get(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = self.local.get(hash).await? {
+            return Ok(Some(data));
+        }
+        match self.remote.get(hash).await? {
+            Some(data) => {
+                self.local.put(hash, data.clone()).await?;
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.local.put(hash, data.clone()).await?;
+        self.remote.put(hash, data).await
+    }
+
+    async fn get_blob(&self, hash: &str) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = self.local.get_blob(hash).await? {
+            return Ok(Some(data));
+        }
+        match self.remote.get_blob(hash).await? {
+            Some(data) => {
+                self.local.put_blob(hash, data.clone()).await?;
+                Ok(Some(data))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put_blob(&self, hash: &str, data: Vec<u8>) -> Result<()> {
+        self.local.put_blob(hash, data.clone()).await?;
+        self.remote.put_blob(hash, data).await
+    }
+
+    async fn clear(&self) -> Result<()> {
+        self.local.clear().await
+    }
+
+    async fn stats(&self) -> Result<(usize, u64)> {
+        self.local.stats().await
+    }
+}
+
+/// Build a backend from an address string:
+/// - empty / `file://path` -> `LocalBackend` rooted at the workspace (or the given path)
+/// - `http://` / `https://` -> `HttpBackend`
+/// - `s3://bucket` -> `S3Backend`, layered under the local cache via `TieredBackend`
+/// - `grpc://` -> not implemented yet
+pub fn from_addr(addr: &str, root: impl AsRef<Path>) -> Result<Arc<dyn CacheBackend>> {
+    if addr.is_empty() {
+        return Ok(Arc::new(LocalBackend::new(root)?));
+    }
+
+    if addr.starts_with("http://") || addr.starts_with("https://") {
+        return Ok(Arc::new(HttpBackend::new(addr)));
+    }
+
+    if let Some(bucket) = addr.strip_prefix("s3://") {
+        let mut config = crate::cloud::load_config()?.s3.unwrap_or_default();
+        config.bucket = bucket.to_string();
+        let local: Arc<dyn CacheBackend> = Arc::new(LocalBackend::new(&root)?);
+        let remote: Arc<dyn CacheBackend> = Arc::new(S3Backend::from_config(&config)?);
+        return Ok(Arc::new(TieredBackend::new(local, remote)));
+    }
+
+    if addr.starts_with("grpc://") {
+        return Err(anyhow!("grpc cache backend is not implemented yet"));
+    }
+
+    let path = addr.strip_prefix("file://").unwrap_or(addr);
+    Ok(Arc::new(LocalBackend::new(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_local_backend_roundtrip() {
+        let dir = tempdir().unwrap();
+        let backend = LocalBackend::new(dir.path()).unwrap();
+
+        assert!(!backend.has("abc").await.unwrap());
+        backend.put("abc", b"hello".to_vec()).await.unwrap();
+        assert!(backend.has("abc").await.unwrap());
+        assert_eq!(backend.get("abc").await.unwrap(), Some(b"hello".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_tiered_backend_writes_through_and_fills_local_on_miss() {
+        let local_dir = tempdir().unwrap();
+        let remote_dir = tempdir().unwrap();
+        let local: Arc<dyn CacheBackend> = Arc::new(LocalBackend::new(local_dir.path()).unwrap());
+        let remote: Arc<dyn CacheBackend> = Arc::new(LocalBackend::new(remote_dir.path()).unwrap());
+        let tiered = TieredBackend::new(Arc::clone(&local), Arc::clone(&remote));
+
+        tiered.put("k", b"v".to_vec()).await.unwrap();
+        assert_eq!(local.get("k").await.unwrap(), Some(b"v".to_vec()));
+        assert_eq!(remote.get("k").await.unwrap(), Some(b"v".to_vec()));
+
+        // Simulate a cold local cache: remote still has it, should populate local
+        let fresh_local_dir = tempdir().unwrap();
+        let fresh_local: Arc<dyn CacheBackend> =
+            Arc::new(LocalBackend::new(fresh_local_dir.path()).unwrap());
+        let tiered2 = TieredBackend::new(Arc::clone(&fresh_local), remote);
+        assert_eq!(tiered2.get("k").await.unwrap(), Some(b"v".to_vec()));
+        assert_eq!(fresh_local.get("k").await.unwrap(), Some(b"v".to_vec()));
+    }
+}
@@ -7,15 +7,29 @@
 //! - Sync download (blocking for cache hit)
 
 use anyhow::{anyhow, Result};
+use rusty_s3::actions::{AbortMultipartUpload, CompleteMultipartUpload, CreateMultipartUpload};
 use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
+
+use crate::worker::{WorkerReporter, WorkerState};
+
+/// Artifacts larger than this use multipart upload instead of a single PUT
+const MULTIPART_THRESHOLD: usize = 64 * 1024 * 1024;
+/// S3 requires every part but the last to be at least 5 MiB
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+/// Bounded concurrency for part uploads
+const MULTIPART_CONCURRENCY: usize = 4;
 
 /// Cloud configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CloudConfig {
     pub s3: Option<S3Config>,
+    /// Optional build-event webhook, see `crate::webhook`
+    pub webhook: Option<crate::webhook::WebhookConfig>,
 }
 
 /// S3/R2 configuration
@@ -135,38 +149,191 @@ impl CloudCache {
         self.enabled
     }
 
-    /// Upload artifact
+    /// Upload artifact. Payloads larger than `MULTIPART_THRESHOLD` (a fixed
+    /// 64 MiB, not configurable per cache) go through multipart upload
+    /// instead of a single PUT, since a single
+    /// signed PUT has no way to resume or bound memory for big build outputs.
     pub async fn upload(&self, hash: &str, data: Vec<u8>) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
         let key = format!("artifacts/{}", hash);
-        let url = self.bucket.put_object(Some(&self.credentials), &key)
-            .sign(Duration::from_secs(300));
 
-        self.client
-            .put(url)
-            .body(data)
+        if data.len() > MULTIPART_THRESHOLD {
+            self.upload_multipart(&key, data).await?;
+        } else {
+            let url = self.bucket.put_object(Some(&self.credentials), &key)
+                .sign(Duration::from_secs(300));
+
+            self.client
+                .put(url)
+                .body(data)
+                .send()
+                .await
+                .map_err(|e| anyhow!("Upload failed: {}", e))?;
+        }
+
+        tracing::info!("☁️ Uploaded: {}", hash);
+        Ok(())
+    }
+
+    /// Upload `data` as an S3 multipart upload: split into parts of at least
+    /// `MULTIPART_PART_SIZE` bytes (the last part may be smaller), PUT them
+    /// concurrently under a semaphore, then stitch the result together with
+    /// `CompleteMultipartUpload`. Any part failure aborts the whole upload so
+    /// no orphaned parts keep accruing storage billing.
+    async fn upload_multipart(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let create = self.bucket.create_multipart_upload(Some(&self.credentials), key);
+        let url = create.sign(Duration::from_secs(300));
+        let resp = self
+            .client
+            .post(url)
             .send()
             .await
-            .map_err(|e| anyhow!("Upload failed: {}", e))?;
+            .map_err(|e| anyhow!("create multipart upload failed: {}", e))?;
+        let body = resp.text().await?;
+        let upload_id = CreateMultipartUpload::parse_response(&body)
+            .map_err(|e| anyhow!("could not parse create-multipart-upload response: {}", e))?
+            .upload_id;
+
+        let parts: Vec<Vec<u8>> = data.chunks(MULTIPART_PART_SIZE).map(|c| c.to_vec()).collect();
+        let semaphore = Arc::new(Semaphore::new(MULTIPART_CONCURRENCY));
+        let mut handles = Vec::with_capacity(parts.len());
+
+        for (i, part) in parts.into_iter().enumerate() {
+            let part_number = (i + 1) as u16;
+            let semaphore = Arc::clone(&semaphore);
+            let bucket = self.bucket.clone();
+            let credentials = self.credentials.clone();
+            let client = self.client.clone();
+            let key = key.to_string();
+            let upload_id = upload_id.clone();
+
+            handles.push(tokio::spawn(async move {
+                let _permit = semaphore.acquire().await.unwrap();
+
+                let action = bucket.upload_part(Some(&credentials), &key, part_number, &upload_id);
+                let url = action.sign(Duration::from_secs(300));
+
+                let resp = client
+                    .put(url)
+                    .body(part)
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("part {} upload failed: {}", part_number, e))?;
+
+                if !resp.status().is_success() {
+                    return Err(anyhow!("part {} upload failed with status {}", part_number, resp.status()));
+                }
+
+                let etag = resp
+                    .headers()
+                    .get("ETag")
+                    .ok_or_else(|| anyhow!("part {} response missing ETag", part_number))?
+                    .to_str()?
+                    .to_string();
+
+                Ok((part_number, etag))
+            }));
+        }
+
+        let mut etags: Vec<(u16, String)> = Vec::with_capacity(handles.len());
+        let mut failed = false;
+
+        for handle in handles {
+            match handle.await {
+                Ok(Ok(entry)) => etags.push(entry),
+                Ok(Err(e)) => {
+                    tracing::error!("{}", e);
+                    failed = true;
+                }
+                Err(e) => {
+                    tracing::error!("part upload task panicked: {}", e);
+                    failed = true;
+                }
+            }
+        }
+
+        if failed {
+            self.abort_multipart(key, &upload_id).await;
+            return Err(anyhow!("multipart upload aborted: one or more parts failed"));
+        }
+
+        etags.sort_by_key(|(part_number, _)| *part_number);
+        let etag_refs = etags.iter().map(|(_, tag)| tag.as_str());
+
+        let complete = self.bucket.complete_multipart_upload(
+            Some(&self.credentials),
+            key,
+            &upload_id,
+            etag_refs,
+        );
+        let url = complete.sign(Duration::from_secs(300));
+        let body = complete.body();
+
+        let resp = self
+            .client
+            .post(url)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("complete multipart upload failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            self.abort_multipart(key, &upload_id).await;
+            return Err(anyhow!("complete multipart upload failed with status {}", resp.status()));
+        }
 
-        tracing::info!("☁️ Uploaded: {}", hash);
         Ok(())
     }
 
-    /// Upload in background (fire and forget)
-    pub fn upload_background(hash: String, data: Vec<u8>) {
+    /// Best-effort `AbortMultipartUpload` so a failed upload doesn't leave
+    /// orphaned parts accruing storage cost.
+    async fn abort_multipart(&self, key: &str, upload_id: &str) {
+        let action = self.bucket.abort_multipart_upload(Some(&self.credentials), key, upload_id);
+        let url = action.sign(Duration::from_secs(300));
+
+        if let Err(e) = self.client.delete(url).send().await {
+            tracing::error!("abort multipart upload failed for {}: {}", key, e);
+        }
+    }
+
+    /// Upload in background (fire and forget). `reporter`, if given, is told
+    /// about the upload so it shows up in `neex workers` - see
+    /// [`crate::worker::WorkerReporter`] for why this takes a trait object
+    /// instead of a concrete registry.
+    pub fn upload_background(hash: String, data: Vec<u8>, reporter: Option<Arc<dyn WorkerReporter>>) {
         tokio::spawn(async move {
-            match CloudCache::try_new() {
-                Ok(Some(cloud)) => {
-                    if let Err(e) = cloud.upload(&hash, data).await {
-                        tracing::error!("Background upload failed: {}", e);
+            let worker_id = match &reporter {
+                Some(r) => match r.register(format!("cloud-upload:{hash}")).await {
+                    Ok(id) => Some(id),
+                    Err(e) => {
+                        tracing::warn!("Failed to register upload worker: {}", e);
+                        None
                     }
+                },
+                None => None,
+            };
+
+            let result = match CloudCache::try_new() {
+                Ok(Some(cloud)) => cloud.upload(&hash, data).await,
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = &result {
+                tracing::error!("Background upload failed: {}", e);
+            }
+
+            if let (Some(r), Some(id)) = (&reporter, worker_id) {
+                let state = match &result {
+                    Ok(()) => WorkerState::Dead("completed".to_string()),
+                    Err(e) => WorkerState::Dead(e.to_string()),
+                };
+                if let Err(e) = r.report(id, state, None).await {
+                    tracing::warn!("Failed to report upload worker state: {}", e);
                 }
-                Ok(None) => {}
-                Err(e) => tracing::error!("Cloud init failed: {}", e),
             }
         });
     }
@@ -192,6 +359,30 @@ impl CloudCache {
         }
     }
 
+    /// Sign a short-lived GET URL for an artifact so a peer can fetch it
+    /// directly from the bucket without proxying the bytes through us.
+    pub fn presigned_get_url(&self, hash: &str, ttl: Duration) -> Result<String> {
+        if !self.enabled {
+            return Err(anyhow!("cloud cache is not enabled"));
+        }
+
+        let key = format!("artifacts/{}", hash);
+        let url = self.bucket.get_object(Some(&self.credentials), &key).sign(ttl);
+        Ok(url.to_string())
+    }
+
+    /// Sign a short-lived PUT URL for an artifact, e.g. so a peer can push a
+    /// cache miss straight to the bucket instead of routing it through us.
+    pub fn presigned_put_url(&self, hash: &str, ttl: Duration) -> Result<String> {
+        if !self.enabled {
+            return Err(anyhow!("cloud cache is not enabled"));
+        }
+
+        let key = format!("artifacts/{}", hash);
+        let url = self.bucket.put_object(Some(&self.credentials), &key).sign(ttl);
+        Ok(url.to_string())
+    }
+
     /// Check connection
     pub async fn ping(&self) -> Result<bool> {
         let mut action = self.bucket.list_objects_v2(Some(&self.credentials));
@@ -10,8 +10,9 @@
 use anyhow::{anyhow, Result};
 use petgraph::algo::{is_cyclic_directed, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 
 /// A workspace package node
@@ -58,12 +59,27 @@ struct PackageJson {
     peer_dependencies: Option<HashMap<String, String>>,
 }
 
+/// Every dependency name a package.json declares, across dependencies,
+/// devDependencies and peerDependencies.
+fn dependency_names(pkg: &PackageJson) -> Vec<String> {
+    [&pkg.dependencies, &pkg.dev_dependencies, &pkg.peer_dependencies]
+        .into_iter()
+        .flatten()
+        .flat_map(|m| m.keys().cloned())
+        .collect()
+}
+
 /// Dependency Graph for workspace packages
 pub struct DepGraph {
     /// The directed graph
     pub graph: DiGraph<WorkspaceNode, ()>,
     /// Map from package name to node index
     name_to_idx: HashMap<String, NodeIndex>,
+    /// Declared dependency names per node (all of dependencies/devDependencies/
+    /// peerDependencies), cached alongside the graph so `upsert_package` can
+    /// recompute just the edges a change affects instead of re-reading every
+    /// package.json the way `build_edges` does.
+    deps: HashMap<NodeIndex, Vec<String>>,
     /// Root directory
     root: PathBuf,
 }
@@ -74,6 +90,7 @@ impl DepGraph {
         Self {
             graph: DiGraph::new(),
             name_to_idx: HashMap::new(),
+            deps: HashMap::new(),
             root: PathBuf::new(),
         }
     }
@@ -84,6 +101,7 @@ impl DepGraph {
         let mut graph = Self {
             graph: DiGraph::new(),
             name_to_idx: HashMap::new(),
+            deps: HashMap::new(),
             root: root.clone(),
         };
 
@@ -143,11 +161,12 @@ impl DepGraph {
         let content = std::fs::read_to_string(&pkg_json_path)?;
         let pkg: PackageJson = serde_json::from_str(&content)?;
 
-        let name = pkg.name.ok_or_else(|| anyhow!("Package has no name: {:?}", ws_path))?;
+        let name = pkg.name.clone().ok_or_else(|| anyhow!("Package has no name: {:?}", ws_path))?;
         let relative_path = ws_path.strip_prefix(&self.root).unwrap_or(ws_path).to_path_buf();
 
         let scripts: Vec<String> =
-            pkg.scripts.map(|s| s.keys().cloned().collect()).unwrap_or_default();
+            pkg.scripts.as_ref().map(|s| s.keys().cloned().collect()).unwrap_or_default();
+        let deps = dependency_names(&pkg);
 
         let node = WorkspaceNode {
             name: name.clone(),
@@ -159,6 +178,7 @@ impl DepGraph {
 
         let idx = self.graph.add_node(node);
         self.name_to_idx.insert(name, idx);
+        self.deps.insert(idx, deps);
 
         Ok(idx)
     }
@@ -233,6 +253,152 @@ impl DepGraph {
         Ok(build_order)
     }
 
+    /// Build a max-parallelism schedule over this graph: instead of
+    /// `get_build_order`'s single flat Vec, packages are released in waves
+    /// as their dependencies finish, so independent packages can build
+    /// concurrently instead of one at a time. Refuses to start on a
+    /// circular dependency, same as `get_build_order`.
+    pub fn build_schedule(&self) -> Result<BuildSchedule<'_>> {
+        if self.has_cycle() {
+            return Err(anyhow!("Circular dependency detected!"));
+        }
+
+        let mut remaining = HashMap::new();
+        let mut ready = VecDeque::new();
+        let mut pending = HashSet::new();
+
+        for idx in self.graph.node_indices() {
+            let out_degree = self
+                .graph
+                .neighbors_directed(idx, petgraph::Direction::Outgoing)
+                .count();
+
+            remaining.insert(idx, out_degree);
+            pending.insert(idx);
+            if out_degree == 0 {
+                ready.push_back(idx);
+            }
+        }
+
+        Ok(BuildSchedule {
+            graph: self,
+            remaining,
+            ready,
+            pending,
+        })
+    }
+
+    /// Re-parse `ws_path`'s package.json and fold the change into the graph
+    /// in place: a package already in the graph has its `WorkspaceNode`
+    /// (version, scripts) and its own outgoing edges replaced; a package
+    /// seen for the first time is inserted and also gains incoming edges
+    /// from any already-known package whose dependency list was waiting on
+    /// this name. Either way, only the edges this one package touches are
+    /// recomputed - every other node's edges are left untouched, unlike
+    /// `build_edges`, which re-reads the whole workspace.
+    pub fn upsert_package(&mut self, ws_path: &Path) -> Result<()> {
+        let pkg_json_path = ws_path.join("package.json");
+        let content = std::fs::read_to_string(&pkg_json_path)?;
+        let pkg: PackageJson = serde_json::from_str(&content)?;
+
+        let name = pkg.name.clone().ok_or_else(|| anyhow!("Package has no name: {:?}", ws_path))?;
+        let relative_path = ws_path.strip_prefix(&self.root).unwrap_or(ws_path).to_path_buf();
+        let scripts: Vec<String> =
+            pkg.scripts.as_ref().map(|s| s.keys().cloned().collect()).unwrap_or_default();
+        let deps = dependency_names(&pkg);
+
+        let idx = if let Some(&idx) = self.name_to_idx.get(&name) {
+            {
+                let node = &mut self.graph[idx];
+                node.path = relative_path;
+                node.package_json_path = pkg_json_path;
+                node.version = pkg.version;
+                node.scripts = scripts;
+            }
+
+            // Drop this node's own outgoing edges so they can be rebuilt
+            // below from its current dependency list.
+            let stale: Vec<_> = self
+                .graph
+                .edges_directed(idx, petgraph::Direction::Outgoing)
+                .map(|e| e.id())
+                .collect();
+            for edge in stale {
+                self.graph.remove_edge(edge);
+            }
+
+            idx
+        } else {
+            let node = WorkspaceNode {
+                name: name.clone(),
+                path: relative_path,
+                package_json_path: pkg_json_path,
+                version: pkg.version,
+                scripts,
+            };
+            let idx = self.graph.add_node(node);
+            self.name_to_idx.insert(name.clone(), idx);
+
+            // Some already-known package may have declared a dependency on
+            // this name before this node existed, in which case neither
+            // `build_edges` nor a prior `upsert_package` could have wired it
+            // up - do it now that the node is here.
+            for (&other_idx, other_deps) in &self.deps {
+                if other_idx != idx && other_deps.iter().any(|d| d == &name) {
+                    self.graph.add_edge(other_idx, idx, ());
+                }
+            }
+
+            idx
+        };
+
+        for dep_name in &deps {
+            if let Some(&dep_idx) = self.name_to_idx.get(dep_name) {
+                self.graph.add_edge(idx, dep_idx, ());
+            }
+        }
+        self.deps.insert(idx, deps);
+
+        Ok(())
+    }
+
+    /// Remove `name` from the graph - the counterpart to `upsert_package`
+    /// for a package that disappeared from disk. Petgraph drops every edge
+    /// incident to the removed node automatically, so no separate edge
+    /// bookkeeping is needed here.
+    pub fn remove_package(&mut self, name: &str) {
+        let Some(idx) = self.name_to_idx.remove(name) else {
+            return;
+        };
+
+        // `remove_node` moves the last node into the slot being removed
+        // (petgraph uses swap-remove under the hood), so whichever node was
+        // last now lives at `idx` - keep our own index caches in sync.
+        let last_idx = NodeIndex::new(self.graph.node_count() - 1);
+        self.graph.remove_node(idx);
+        self.deps.remove(&idx);
+
+        if last_idx != idx {
+            if let Some(moved) = self.graph.node_weight(idx) {
+                self.name_to_idx.insert(moved.name.clone(), idx);
+            }
+            if let Some(moved_deps) = self.deps.remove(&last_idx) {
+                self.deps.insert(idx, moved_deps);
+            }
+        }
+    }
+
+    /// Name of the package rooted at `ws_path`, if one is in the graph -
+    /// used to resolve a deleted package.json back to a name for
+    /// `remove_package`.
+    pub fn package_name_at(&self, ws_path: &Path) -> Option<String> {
+        let relative_path = ws_path.strip_prefix(&self.root).unwrap_or(ws_path);
+        self.graph
+            .node_indices()
+            .find(|&idx| self.graph[idx].path == relative_path)
+            .map(|idx| self.graph[idx].name.clone())
+    }
+
     /// Get packages affected by a change in the given package
     pub fn get_affected(&self, package_name: &str) -> Vec<&WorkspaceNode> {
         let Some(&start_idx) = self.name_to_idx.get(package_name) else {
@@ -289,6 +455,63 @@ impl Default for DepGraph {
 // Re-export old DependencyGraph for compatibility
 pub type DependencyGraph = DepGraph;
 
+/// Max-parallelism build ordering produced by [`DepGraph::build_schedule`].
+/// Each node's remaining-dependency count is its out-degree in the
+/// dependent -> dependency graph; a node joins the ready set once that
+/// count hits zero, and [`mark_done`](BuildSchedule::mark_done) decrements
+/// the count of every package that depends on the one that just finished
+/// (its incoming neighbors), releasing whichever of those reach zero in turn.
+pub struct BuildSchedule<'g> {
+    graph: &'g DepGraph,
+    remaining: HashMap<NodeIndex, usize>,
+    ready: VecDeque<NodeIndex>,
+    pending: HashSet<NodeIndex>,
+}
+
+impl<'g> BuildSchedule<'g> {
+    /// Hand out every package that's currently ready to build. A package is
+    /// only ever returned once - call `mark_done` as each one finishes so
+    /// its dependents can become ready in turn. An empty Vec means either
+    /// the whole schedule is drained (see [`is_drained`](Self::is_drained))
+    /// or everything ready right now is already in flight.
+    pub fn next_ready(&mut self) -> Vec<&'g WorkspaceNode> {
+        self.ready
+            .drain(..)
+            .map(|idx| &self.graph.graph[idx])
+            .collect()
+    }
+
+    /// Report that `name` has finished building. Decrements the
+    /// remaining-dependency count of every package that depends on it;
+    /// whichever reach zero join the ready set for the next `next_ready`
+    /// call. A package with no dependents (e.g. the final one in a chain)
+    /// simply has nothing to release, and the schedule drains cleanly.
+    pub fn mark_done(&mut self, name: &str) {
+        let Some(&idx) = self.graph.name_to_idx.get(name) else {
+            return;
+        };
+        self.pending.remove(&idx);
+
+        for dependent in self
+            .graph
+            .graph
+            .neighbors_directed(idx, petgraph::Direction::Incoming)
+        {
+            if let Some(count) = self.remaining.get_mut(&dependent) {
+                *count -= 1;
+                if *count == 0 {
+                    self.ready.push_back(dependent);
+                }
+            }
+        }
+    }
+
+    /// True once every package in the schedule has been marked done.
+    pub fn is_drained(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,4 +641,182 @@ mod tests {
         assert!(names.contains(&"@my/ui"));
         assert!(names.contains(&"@my/web"));
     }
+
+    #[test]
+    fn test_build_schedule_runs_independent_packages_in_one_wave() {
+        let mut dep_graph = DepGraph::new();
+
+        // utils <- ui <- web, utils <- cli (ui and cli are independent)
+        let utils_idx = dep_graph.graph.add_node(WorkspaceNode {
+            name: "@my/utils".into(),
+            path: PathBuf::from("packages/utils"),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+        dep_graph.name_to_idx.insert("@my/utils".into(), utils_idx);
+
+        let ui_idx = dep_graph.graph.add_node(WorkspaceNode {
+            name: "@my/ui".into(),
+            path: PathBuf::from("packages/ui"),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+        dep_graph.name_to_idx.insert("@my/ui".into(), ui_idx);
+
+        let cli_idx = dep_graph.graph.add_node(WorkspaceNode {
+            name: "@my/cli".into(),
+            path: PathBuf::from("packages/cli"),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+        dep_graph.name_to_idx.insert("@my/cli".into(), cli_idx);
+
+        let web_idx = dep_graph.graph.add_node(WorkspaceNode {
+            name: "@my/web".into(),
+            path: PathBuf::from("packages/web"),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+        dep_graph.name_to_idx.insert("@my/web".into(), web_idx);
+
+        dep_graph.graph.add_edge(ui_idx, utils_idx, ());
+        dep_graph.graph.add_edge(cli_idx, utils_idx, ());
+        dep_graph.graph.add_edge(web_idx, ui_idx, ());
+
+        let mut schedule = dep_graph.build_schedule().unwrap();
+
+        // Wave 1: only utils has no remaining dependencies
+        let wave1 = schedule.next_ready();
+        assert_eq!(wave1.len(), 1);
+        assert_eq!(wave1[0].name, "@my/utils");
+        schedule.mark_done("@my/utils");
+
+        // Wave 2: ui and cli both became ready at once
+        let mut wave2: Vec<&str> = schedule.next_ready().iter().map(|n| n.name.as_str()).collect();
+        wave2.sort();
+        assert_eq!(wave2, vec!["@my/cli", "@my/ui"]);
+
+        // web isn't ready yet - cli hasn't finished, but that doesn't block it
+        assert!(schedule.next_ready().is_empty());
+        schedule.mark_done("@my/cli");
+        assert!(schedule.next_ready().is_empty());
+        assert!(!schedule.is_drained());
+
+        schedule.mark_done("@my/ui");
+        let wave3 = schedule.next_ready();
+        assert_eq!(wave3.len(), 1);
+        assert_eq!(wave3[0].name, "@my/web");
+
+        // web has no dependents - marking it done should drain cleanly
+        schedule.mark_done("@my/web");
+        assert!(schedule.is_drained());
+    }
+
+    fn write_package(dir: &Path, rel: &str, name: &str, deps: &[&str]) {
+        let ws = dir.join(rel);
+        std::fs::create_dir_all(&ws).unwrap();
+        let deps_json: String =
+            deps.iter().map(|d| format!("\"{d}\": \"1.0.0\"")).collect::<Vec<_>>().join(",");
+        std::fs::write(
+            ws.join("package.json"),
+            format!(r#"{{"name": "{name}", "version": "1.0.0", "dependencies": {{{deps_json}}}}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_upsert_package_wires_up_dependents_seen_before_the_dependency() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        // ui depends on utils, but utils doesn't exist on disk yet.
+        write_package(root, "packages/ui", "@my/ui", &["@my/utils"]);
+
+        let mut graph = DepGraph::new();
+        graph.upsert_package(&root.join("packages/ui")).unwrap();
+        assert_eq!(graph.package_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+
+        // utils shows up later - ui's pending dependency edge should appear
+        // without re-scanning ui's package.json again.
+        write_package(root, "packages/utils", "@my/utils", &[]);
+        graph.upsert_package(&root.join("packages/utils")).unwrap();
+
+        assert_eq!(graph.package_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        let affected = graph.get_affected("@my/utils");
+        let names: Vec<&str> = affected.iter().map(|n| n.name.as_str()).collect();
+        assert!(names.contains(&"@my/ui"));
+    }
+
+    #[test]
+    fn test_upsert_package_updates_existing_node_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_package(root, "packages/utils", "@my/utils", &[]);
+
+        let mut graph = DepGraph::new();
+        graph.upsert_package(&root.join("packages/utils")).unwrap();
+        assert_eq!(graph.get_package("@my/utils").unwrap().version, Some("1.0.0".to_string()));
+
+        std::fs::write(
+            root.join("packages/utils/package.json"),
+            r#"{"name": "@my/utils", "version": "2.0.0"}"#,
+        )
+        .unwrap();
+        graph.upsert_package(&root.join("packages/utils")).unwrap();
+
+        assert_eq!(graph.package_count(), 1, "should update in place, not duplicate");
+        assert_eq!(graph.get_package("@my/utils").unwrap().version, Some("2.0.0".to_string()));
+    }
+
+    #[test]
+    fn test_remove_package_drops_node_and_its_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write_package(root, "packages/utils", "@my/utils", &[]);
+        write_package(root, "packages/ui", "@my/ui", &["@my/utils"]);
+
+        let mut graph = DepGraph::new();
+        graph.upsert_package(&root.join("packages/utils")).unwrap();
+        graph.upsert_package(&root.join("packages/ui")).unwrap();
+        assert_eq!(graph.edge_count(), 1);
+
+        let name = graph.package_name_at(&root.join("packages/utils")).unwrap();
+        graph.remove_package(&name);
+
+        assert_eq!(graph.package_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(graph.get_package("@my/utils").is_none());
+        assert!(graph.get_package("@my/ui").is_some());
+    }
+
+    #[test]
+    fn test_build_schedule_refuses_cyclic_graph() {
+        let mut dep_graph = DepGraph::new();
+
+        let idx_a = dep_graph.graph.add_node(WorkspaceNode {
+            name: "A".into(),
+            path: PathBuf::new(),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+        let idx_b = dep_graph.graph.add_node(WorkspaceNode {
+            name: "B".into(),
+            path: PathBuf::new(),
+            package_json_path: PathBuf::new(),
+            version: None,
+            scripts: vec![],
+        });
+
+        dep_graph.graph.add_edge(idx_a, idx_b, ());
+        dep_graph.graph.add_edge(idx_b, idx_a, ());
+
+        assert!(dep_graph.build_schedule().is_err());
+    }
 }
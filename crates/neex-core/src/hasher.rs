@@ -4,16 +4,21 @@
 //! - 10x faster than SHA-256
 //! - Parallel file hashing with Rayon
 //! - Respects .gitignore patterns
-//! - Incremental updates
+//! - Incremental updates - skips re-hashing files whose size/mtime are unchanged
+//! - Files at or above `mmap_threshold` are memory-mapped and hashed with
+//!   `update_mmap_rayon` instead of read fully into RAM
 
 use anyhow::Result;
 use blake3::Hasher as Blake3Hasher;
 use ignore::WalkBuilder;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use tokio::sync::{mpsc, Semaphore};
 
 /// File hash result
 #[derive(Debug, Clone)]
@@ -23,29 +28,151 @@ pub struct FileHash {
     pub size: u64,
 }
 
+/// A cached hash plus the size/mtime it was computed from. A file whose
+/// `stat()` still matches both fields is assumed unchanged, so its content
+/// never needs to be read again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFileHash {
+    hash: String,
+    size: u64,
+    mtime: u64,
+}
+
+/// Persisted size+mtime index, written to `.neex/hashcache` so a fresh
+/// process doesn't have to re-read every file on its first `hash_all`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HashCache {
+    entries: HashMap<PathBuf, CachedFileHash>,
+}
+
+impl HashCache {
+    /// Missing or corrupt cache just means a full rehash, not a hard error
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// At or above this size, hashing switches from `fs::read` + single-threaded
+/// `blake3::hash` to memory-mapping the file and hashing its chunks in
+/// parallel across the rayon pool, so a multi-gigabyte file never has to be
+/// fully loaded into RAM just to be hashed.
+const DEFAULT_MMAP_THRESHOLD: u64 = 8 * 1024 * 1024; // 8 MiB
+
+fn mtime_secs(meta: &fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Hash `path`, whose size is already known from a prior `stat()`. Files at
+/// or above `threshold` are memory-mapped and hashed in parallel via
+/// `update_mmap_rayon`; smaller files keep the plain read-then-hash path,
+/// which avoids the mmap/chunking overhead for the common case.
+fn hash_content(path: &Path, size: u64, threshold: u64) -> Result<String> {
+    if size >= threshold {
+        let mut hasher = Blake3Hasher::new();
+        hasher.update_mmap_rayon(path)?;
+        Ok(hasher.finalize().to_hex().to_string())
+    } else {
+        let content = fs::read(path)?;
+        Ok(blake3::hash(&content).to_hex().to_string())
+    }
+}
+
+/// Hash a single file for `hash_all_stream`, reusing a cached hash when the
+/// file's size/mtime still match. Errors (missing file, permission denied,
+/// ...) propagate to the caller instead of being swallowed. The mmap path
+/// runs on the blocking pool since `update_mmap_rayon` is synchronous,
+/// CPU-bound work that would otherwise stall the async executor.
+async fn hash_one(path: &Path, disk_cache: &HashCache, mmap_threshold: u64) -> Result<FileHash> {
+    let meta = tokio::fs::metadata(path).await?;
+    let size = meta.len();
+    let mtime = mtime_secs(&meta);
+
+    if let Some(cached) = disk_cache.entries.get(path) {
+        if cached.size == size && cached.mtime == mtime {
+            return Ok(FileHash {
+                path: path.to_path_buf(),
+                hash: cached.hash.clone(),
+                size,
+            });
+        }
+    }
+
+    let hash = if size >= mmap_threshold {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || hash_content(&path, size, mmap_threshold)).await??
+    } else {
+        let content = tokio::fs::read(path).await?;
+        blake3::hash(&content).to_hex().to_string()
+    };
+
+    Ok(FileHash {
+        path: path.to_path_buf(),
+        hash,
+        size,
+    })
+}
+
 /// Main hasher struct
 pub struct Hasher {
     root: PathBuf,
     cache: Mutex<HashMap<PathBuf, FileHash>>,
+    disk_cache: Mutex<HashCache>,
+    disk_cache_path: PathBuf,
+    /// Files at or above this size are memory-mapped and hashed with
+    /// `update_mmap_rayon` instead of read fully into RAM
+    mmap_threshold: u64,
 }
 
 impl Hasher {
     /// Create a new hasher for the given root directory
     pub fn new(root: impl AsRef<Path>) -> Self {
+        let root = root.as_ref().to_path_buf();
+        let disk_cache_path = root.join(".neex").join("hashcache");
+        let disk_cache = HashCache::load(&disk_cache_path);
+
         Self {
-            root: root.as_ref().to_path_buf(),
+            root,
             cache: Mutex::new(HashMap::new()),
+            disk_cache: Mutex::new(disk_cache),
+            disk_cache_path,
+            mmap_threshold: DEFAULT_MMAP_THRESHOLD,
         }
     }
 
-    /// Hash a single file using BLAKE3
+    /// Override the size above which files are memory-mapped instead of
+    /// read fully into RAM
+    pub fn with_mmap_threshold(mut self, threshold: u64) -> Self {
+        self.mmap_threshold = threshold;
+        self
+    }
+
+    /// Hash a single file using BLAKE3, memory-mapping it if it's at or
+    /// above `mmap_threshold`
     pub fn hash_file(&self, path: impl AsRef<Path>) -> Result<String> {
-        let content = fs::read(path.as_ref())?;
-        let hash = blake3::hash(&content);
-        Ok(hash.to_hex().to_string())
+        let path = path.as_ref();
+        let size = fs::metadata(path)?.len();
+        hash_content(path, size, self.mmap_threshold)
     }
 
-    /// Hash all files in directory (parallel with Rayon)
+    /// Hash all files in directory (parallel with Rayon). A file whose size
+    /// and mtime match the on-disk cache is skipped entirely and its stored
+    /// hash reused; only changed files are actually read and re-hashed.
     /// Target: 10,000 files < 100ms
     pub fn hash_all(&self) -> Result<Vec<FileHash>> {
         let files: Vec<PathBuf> = WalkBuilder::new(&self.root)
@@ -59,31 +186,108 @@ impl Hasher {
             .map(|e| e.path().to_path_buf())
             .collect();
 
-        // Parallel hashing with Rayon
-        let results: Vec<FileHash> = files
+        let disk_cache = self.disk_cache.lock().unwrap().clone();
+        let mmap_threshold = self.mmap_threshold;
+
+        // Parallel hashing with Rayon, reusing cached hashes for unchanged files
+        let results: Vec<(FileHash, u64)> = files
             .par_iter()
             .filter_map(|path| {
-                let content = fs::read(path).ok()?;
-                let hash = blake3::hash(&content);
-                let size = content.len() as u64;
-                
-                Some(FileHash {
-                    path: path.clone(),
-                    hash: hash.to_hex().to_string(),
-                    size,
-                })
+                let meta = fs::metadata(path).ok()?;
+                let size = meta.len();
+                let mtime = mtime_secs(&meta);
+
+                if let Some(cached) = disk_cache.entries.get(path) {
+                    if cached.size == size && cached.mtime == mtime {
+                        return Some((
+                            FileHash { path: path.clone(), hash: cached.hash.clone(), size },
+                            mtime,
+                        ));
+                    }
+                }
+
+                let hash = hash_content(path, size, mmap_threshold).ok()?;
+
+                Some((
+                    FileHash { path: path.clone(), hash, size },
+                    mtime,
+                ))
             })
             .collect();
 
-        // Update cache
+        // Update in-memory cache and persist the size/mtime index
         {
             let mut cache = self.cache.lock().unwrap();
-            for result in &results {
-                cache.insert(result.path.clone(), result.clone());
+            let mut disk_cache = self.disk_cache.lock().unwrap();
+
+            for (file, mtime) in &results {
+                cache.insert(file.path.clone(), file.clone());
+                disk_cache.entries.insert(
+                    file.path.clone(),
+                    CachedFileHash {
+                        hash: file.hash.clone(),
+                        size: file.size,
+                        mtime: *mtime,
+                    },
+                );
+            }
+
+            if let Err(e) = disk_cache.save(&self.disk_cache_path) {
+                tracing::warn!("Failed to persist hash cache: {}", e);
             }
         }
 
-        Ok(results)
+        Ok(results.into_iter().map(|(file, _)| file).collect())
+    }
+
+    /// Async streaming variant of `hash_all`: walks the tree, then hashes
+    /// files concurrently under a `Semaphore` of at most `concurrency`
+    /// in-flight reads, sending `(path, result)` to the returned channel as
+    /// each one finishes. Unlike `hash_all`, a file that fails to `stat` or
+    /// read is reported as an `Err` on its path instead of silently dropped,
+    /// and callers can start consuming results before the whole tree is done.
+    pub async fn hash_all_stream(
+        &self,
+        concurrency: usize,
+    ) -> mpsc::Receiver<(PathBuf, Result<FileHash>)> {
+        let (tx, rx) = mpsc::channel(256);
+        let root = self.root.clone();
+        let disk_cache = Arc::new(self.disk_cache.lock().unwrap().clone());
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mmap_threshold = self.mmap_threshold;
+
+        tokio::spawn(async move {
+            let files: Vec<PathBuf> = WalkBuilder::new(&root)
+                .hidden(false)
+                .ignore(true)
+                .git_ignore(true)
+                .git_global(true)
+                .build()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+                .map(|e| e.path().to_path_buf())
+                .collect();
+
+            let mut handles = Vec::with_capacity(files.len());
+
+            for path in files {
+                let semaphore = Arc::clone(&semaphore);
+                let disk_cache = Arc::clone(&disk_cache);
+                let tx = tx.clone();
+
+                handles.push(tokio::spawn(async move {
+                    let _permit = semaphore.acquire().await.unwrap();
+                    let result = hash_one(&path, &disk_cache, mmap_threshold).await;
+                    let _ = tx.send((path, result)).await;
+                }));
+            }
+
+            for handle in handles {
+                let _ = handle.await;
+            }
+        });
+
+        rx
     }
 
     /// Get global hash of all files (for cache key)
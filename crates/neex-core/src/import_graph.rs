@@ -0,0 +1,274 @@
+//! Import Dependency Graph - Affected-target detection
+//!
+//! Features:
+//! - Walks the same tree-sitter AST as `ast_hasher` to collect import/export/require specifiers
+//! - Resolves specifiers to files under `root` (relative paths + index resolution)
+//! - Builds a reverse dependency graph so a changed file can answer "who imports me?"
+//! - `affected()` does a reverse-BFS to find every file transitively impacted by a change
+
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+/// Candidate extensions tried when resolving a bare specifier to a file on disk
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "mts", "cjs", "cts"];
+
+/// Cross-file import graph keyed on file paths as walked from `root` (so they
+/// carry the `root` prefix rather than being repo-relative)
+#[derive(Debug, Default)]
+pub struct ImportGraph {
+    /// file -> files it imports
+    edges: HashMap<PathBuf, Vec<PathBuf>>,
+    /// file -> files that import it (transposed `edges`)
+    reverse_edges: HashMap<PathBuf, Vec<PathBuf>>,
+    root: PathBuf,
+}
+
+impl ImportGraph {
+    /// Build the graph by walking every parseable JS/TS file under `root`
+    pub fn build(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let mut graph = Self {
+            edges: HashMap::new(),
+            reverse_edges: HashMap::new(),
+            root: root.clone(),
+        };
+
+        let files: Vec<PathBuf> = ignore::WalkBuilder::new(&root)
+            .hidden(false)
+            .git_ignore(true)
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|ft| ft.is_file()).unwrap_or(false))
+            .map(|e| e.path().to_path_buf())
+            .filter(|p| crate::ast_hasher::is_parseable(p))
+            .collect();
+
+        for file in files {
+            if let Ok(content) = std::fs::read_to_string(&file) {
+                let specifiers = extract_specifiers(&file, &content).unwrap_or_default();
+                let resolved: Vec<PathBuf> = specifiers
+                    .iter()
+                    .filter_map(|spec| resolve_specifier(&file, spec))
+                    .collect();
+
+                for target in &resolved {
+                    graph
+                        .reverse_edges
+                        .entry(target.clone())
+                        .or_default()
+                        .push(file.clone());
+                }
+
+                graph.edges.insert(file, resolved);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Files directly imported by `file`
+    pub fn dependencies_of(&self, file: &Path) -> &[PathBuf] {
+        self.edges.get(file).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Files that directly import `file`
+    pub fn dependents_of(&self, file: &Path) -> &[PathBuf] {
+        self.reverse_edges
+            .get(file)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Reverse-BFS over the transposed graph: starting from `changed`, find every
+    /// file that transitively depends on at least one changed file.
+    pub fn affected(&self, changed: &[PathBuf]) -> HashSet<PathBuf> {
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let mut queue: Vec<PathBuf> = Vec::new();
+
+        for path in changed {
+            if visited.insert(path.clone()) {
+                queue.push(path.clone());
+            }
+        }
+
+        while let Some(current) = queue.pop() {
+            for dependent in self.dependents_of(&current) {
+                if visited.insert(dependent.clone()) {
+                    queue.push(dependent.clone());
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Map affected files to the workspace packages that own them, using the
+    /// already-discovered `WorkspaceNode` paths from `DepGraph`.
+    pub fn affected_packages<'a>(
+        &self,
+        changed: &[PathBuf],
+        packages: &'a [&'a crate::graph::WorkspaceNode],
+    ) -> HashSet<&'a str> {
+        let affected = self.affected(changed);
+        let mut result = HashSet::new();
+
+        for file in &affected {
+            if let Some(pkg) = packages
+                .iter()
+                .filter(|p| file.starts_with(self.root.join(&p.path)))
+                .max_by_key(|p| p.path.as_os_str().len())
+            {
+                result.insert(pkg.name.as_str());
+            }
+        }
+
+        result
+    }
+}
+
+/// Walk the AST and collect raw specifier strings from imports/exports/requires
+fn extract_specifiers(file: &Path, content: &str) -> Result<Vec<String>> {
+    let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let language = match ext {
+        "ts" | "mts" | "cts" => tree_sitter_typescript::language_typescript(),
+        "tsx" => tree_sitter_typescript::language_tsx(),
+        "js" | "mjs" | "cjs" | "jsx" => tree_sitter_javascript::language(),
+        _ => return Ok(vec![]),
+    };
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .map_err(|e| anyhow::anyhow!("Language error: {}", e))?;
+
+    let tree = match parser.parse(content, None) {
+        Some(t) => t,
+        None => return Ok(vec![]),
+    };
+
+    let mut specifiers = Vec::new();
+    collect_specifiers(&tree.root_node(), content.as_bytes(), &mut specifiers);
+    Ok(specifiers)
+}
+
+fn collect_specifiers(node: &Node, source: &[u8], out: &mut Vec<String>) {
+    match node.kind() {
+        "import_statement" | "export_statement" => {
+            if let Some(source_node) = node.child_by_field_name("source") {
+                out.push(string_literal_text(source_node, source));
+            }
+        }
+        "call_expression" => {
+            if let Some(fn_name) = node.child_by_field_name("function") {
+                if fn_name.kind() == "identifier"
+                    && node_text(fn_name, source) == "require"
+                {
+                    if let Some(args) = node.child_by_field_name("arguments") {
+                        let mut cursor = args.walk();
+                        if let Some(first) = args.children(&mut cursor).find(|c| c.kind() == "string") {
+                            out.push(string_literal_text(first, source));
+                        }
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_specifiers(&child, source, out);
+    }
+}
+
+fn node_text(node: Node, source: &[u8]) -> String {
+    String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()]).to_string()
+}
+
+fn string_literal_text(node: Node, source: &[u8]) -> String {
+    node_text(node, source)
+        .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// Resolve an import specifier relative to the importing file to a file on disk.
+/// Only relative specifiers ("./x", "../x") are resolved; bare package specifiers
+/// are left to the workspace dependency graph.
+///
+/// Shared with `symbol_graph`, which needs the same relative/index resolution
+/// rules before falling back to its own bare-specifier package map.
+pub(crate) fn resolve_specifier(from: &Path, specifier: &str) -> Option<PathBuf> {
+    if !specifier.starts_with('.') {
+        return None;
+    }
+
+    let base = from.parent()?.join(specifier);
+
+    if base.is_file() {
+        return Some(normalize(&base));
+    }
+
+    for ext in RESOLVE_EXTENSIONS {
+        let candidate = append_ext(&base, ext);
+        if candidate.is_file() {
+            return Some(normalize(&candidate));
+        }
+    }
+
+    for ext in RESOLVE_EXTENSIONS {
+        let candidate = base.join(format!("index.{}", ext));
+        if candidate.is_file() {
+            return Some(normalize(&candidate));
+        }
+    }
+
+    None
+}
+
+fn append_ext(path: &Path, ext: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(".");
+    s.push(ext);
+    PathBuf::from(s)
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    path.components().collect::<PathBuf>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_relative_with_extension() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.ts"), "import { b } from './b';").unwrap();
+        fs::write(root.join("b.ts"), "export const b = 1;").unwrap();
+
+        let graph = ImportGraph::build(root).unwrap();
+        let deps = graph.dependencies_of(&root.join("a.ts"));
+        assert_eq!(deps, &[root.join("b.ts")]);
+    }
+
+    #[test]
+    fn test_affected_transitive() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.ts"), "import './b';").unwrap();
+        fs::write(root.join("b.ts"), "import './c';").unwrap();
+        fs::write(root.join("c.ts"), "export const c = 1;").unwrap();
+
+        let graph = ImportGraph::build(root).unwrap();
+        let affected = graph.affected(&[root.join("c.ts")]);
+
+        assert!(affected.contains(&root.join("c.ts")));
+        assert!(affected.contains(&root.join("b.ts")));
+        assert!(affected.contains(&root.join("a.ts")));
+    }
+}
@@ -4,6 +4,8 @@
 //! - BLAKE3 hashing (10x faster than SHA)
 //! - AST-based hashing (ignores comments/whitespace) - KILLER FEATURE
 //! - Workspace dependency graph with topological sort - MONOREPO FEATURE
+//! - Import-level affected-target detection - INCREMENTAL FEATURE
+//! - Symbol-level dependency graph and invalidation - SURGICAL REBUILD FEATURE
 //! - Parallel task scheduler with dependency awareness - PARALLEL FEATURE
 //! - Task execution with output caching - TURBO FEATURE
 //! - Cloud cache (S3/R2) for remote teams - CLOUD FEATURE
@@ -14,14 +16,41 @@
 pub mod hasher;
 pub mod ast_hasher;
 pub mod graph;
+pub mod import_graph;
+pub mod symbols;
+pub mod symbol_graph;
+pub mod artifact_store;
+pub mod cache_backend;
 pub mod cache;
 pub mod runner;
 pub mod scheduler;
+pub mod sim;
 pub mod cloud;
+pub mod task_aliases;
+pub mod webhook;
+pub mod resumable;
+pub mod reporter;
+pub mod merkle;
+pub mod worker;
 
 pub use hasher::Hasher;
 pub use ast_hasher::{hash_ast, is_parseable};
-pub use graph::{DepGraph, WorkspaceNode, DependencyGraph};
-pub use runner::{TaskRunner, TaskOutput};
-pub use scheduler::{Scheduler, SchedulerTask, TaskResult, TaskStatus};
+pub use graph::{BuildSchedule, DepGraph, WorkspaceNode, DependencyGraph};
+pub use import_graph::ImportGraph;
+pub use symbols::{extract_from_file, extract_symbols, FileSymbols, Import, Symbol, SymbolKind};
+pub use symbol_graph::{SymbolGraph, SymbolCache, SymbolName};
+pub use artifact_store::{ArtifactStore, Manifest, BlobEntry};
+pub use cache_backend::{CacheBackend, LocalBackend, HttpBackend, S3Backend, TieredBackend, from_addr};
+pub use runner::{TaskRunner, TaskOutput, CachePolicy};
+pub use scheduler::{
+    ProgressSnapshot, RetryPolicy, Scheduler, SchedulerProgress, SchedulerTask, TaskResult,
+    TaskStatus, WorkerPool,
+};
+pub use sim::{Runtime, SimRuntime, TokioRuntime};
 pub use cloud::{CloudCache, CloudConfig, S3Config, load_config, save_config, get_config_path};
+pub use task_aliases::{check_alias_collisions, load_task_aliases, resolve_alias, TaskAlias};
+pub use webhook::{WebhookConfig, WebhookEmitter, WebhookEvent};
+pub use resumable::{plan_resume, JobState, ResumeAction, ResumeEntry};
+pub use reporter::{CiReporter, Reporter, RunSummary};
+pub use merkle::MerkleTree;
+pub use worker::{Worker, WorkerInfo, WorkerRegistry, WorkerReporter, WorkerState};
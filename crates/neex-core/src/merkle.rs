@@ -0,0 +1,325 @@
+//! Incremental Merkle tree over a workspace's file hashes
+//!
+//! A flat `HashMap<PathBuf, String>` makes a "global hash" expensive: getting
+//! one means pulling every entry, sorting it, and recombining, even when only
+//! one file changed since the last call. Keying the tree by path component
+//! instead means a leaf update only has to recompute the hashes of its
+//! ancestors - O(depth) instead of O(n) - and the root hash (the workspace's
+//! global hash) is just a clone of an already-computed string. Children are
+//! always hashed in sorted order (`BTreeMap` iterates that way for free) so
+//! the root hash is deterministic across runs and machines.
+
+use blake3::Hasher as Blake3Hasher;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+
+/// One node: a leaf carries a file's content hash directly; an interior
+/// node's hash is BLAKE3 over its children's `(name, hash)` pairs in sorted
+/// order.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    hash: Option<String>,
+    children: BTreeMap<OsString, Node>,
+}
+
+impl Node {
+    fn recompute(&mut self) {
+        if self.children.is_empty() {
+            return; // leaf - its hash was set directly by `insert`
+        }
+
+        let mut hasher = Blake3Hasher::new();
+        for (name, child) in &self.children {
+            hasher.update(name.to_string_lossy().as_bytes());
+            if let Some(hash) = &child.hash {
+                hasher.update(hash.as_bytes());
+            }
+        }
+        self.hash = Some(hasher.finalize().to_hex().to_string());
+    }
+}
+
+fn components(path: &Path) -> Vec<OsString> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_os_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Persistent Merkle tree of per-file content hashes, keyed by path
+/// component. [`MerkleTree::root_hash`] is the workspace's global hash.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleTree {
+    root: Node,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set (or update) a leaf's hash, recomputing only the nodes on the path
+    /// back up to the root. Returns each touched node's tree-path key (`""`
+    /// for the root, `/`-joined components otherwise) paired with its new
+    /// hash, root last - a caller can batch these into durable storage, even
+    /// though the tree is always fully rebuildable from its leaf set alone.
+    pub fn insert(&mut self, path: &Path, hash: String) -> Vec<(String, Option<String>)> {
+        let parts = components(path);
+        let mut touched = Vec::new();
+        insert_rec(&mut self.root, &parts, hash, String::new(), &mut touched);
+        touched
+    }
+
+    /// Drop a leaf, recomputing the path back to the root and pruning any
+    /// ancestor left with no children and no hash of its own. Same return
+    /// shape as `insert`, except a pruned node's new hash is `None`.
+    pub fn remove(&mut self, path: &Path) -> Vec<(String, Option<String>)> {
+        let parts = components(path);
+        let mut touched = Vec::new();
+        remove_rec(&mut self.root, &parts, String::new(), &mut touched);
+        touched
+    }
+
+    /// A single leaf or interior node's current hash.
+    pub fn get(&self, path: &Path) -> Option<String> {
+        let parts = components(path);
+        let mut node = &self.root;
+        for part in &parts {
+            node = node.children.get(part)?;
+        }
+        node.hash.clone()
+    }
+
+    /// The workspace's global hash - `None` for a tree with no leaves yet.
+    pub fn root_hash(&self) -> Option<String> {
+        self.root.hash.clone()
+    }
+
+    /// Same as [`root_hash`](Self::root_hash), but an empty tree gets BLAKE3
+    /// of the empty input instead of `None`, so an empty workspace still has
+    /// a well-defined, deterministic hash instead of every caller needing to
+    /// special-case it.
+    pub fn root_hash_or_empty(&self) -> String {
+        self.root_hash()
+            .unwrap_or_else(|| blake3::hash(b"").to_hex().to_string())
+    }
+
+    /// Every `(path, hash)` leaf pair in the tree, for callers that need the
+    /// flat view (e.g. diffing against an externally supplied snapshot that
+    /// isn't itself a `MerkleTree`).
+    pub fn leaves(&self) -> Vec<(PathBuf, String)> {
+        let mut out = Vec::new();
+        collect_leaves(&self.root, &mut PathBuf::new(), &mut out);
+        out
+    }
+
+    /// Paths that changed between `self` and `other`, descending only into
+    /// subtrees whose node hash differs so an unchanged directory is skipped
+    /// entirely instead of walked leaf by leaf.
+    pub fn diff(&self, other: &MerkleTree) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        diff_rec(&self.root, &other.root, &mut PathBuf::new(), &mut out);
+        out
+    }
+}
+
+fn insert_rec(
+    node: &mut Node,
+    parts: &[OsString],
+    hash: String,
+    prefix: String,
+    touched: &mut Vec<(String, Option<String>)>,
+) {
+    match parts.split_first() {
+        None => node.hash = Some(hash),
+        Some((head, rest)) => {
+            let child_prefix = join(&prefix, head);
+            let child = node.children.entry(head.clone()).or_default();
+            insert_rec(child, rest, hash, child_prefix, touched);
+            node.recompute();
+        }
+    }
+    touched.push((prefix, node.hash.clone()));
+}
+
+/// Returns whether `node` is now empty (no hash, no children) and should be
+/// pruned from its parent.
+fn remove_rec(
+    node: &mut Node,
+    parts: &[OsString],
+    prefix: String,
+    touched: &mut Vec<(String, Option<String>)>,
+) -> bool {
+    match parts.split_first() {
+        None => node.hash = None,
+        Some((head, rest)) => {
+            let child_prefix = join(&prefix, head);
+            if let Some(child) = node.children.get_mut(head) {
+                if remove_rec(child, rest, child_prefix, touched) {
+                    node.children.remove(head);
+                    if node.children.is_empty() {
+                        // Last child gone: clear the now-stale interior hash
+                        // so this node is pruned as well
+                        node.hash = None;
+                    }
+                }
+            }
+            node.recompute();
+        }
+    }
+
+    let is_empty = node.hash.is_none() && node.children.is_empty();
+    touched.push((prefix, if is_empty { None } else { node.hash.clone() }));
+    is_empty
+}
+
+fn join(prefix: &str, component: &OsString) -> String {
+    if prefix.is_empty() {
+        component.to_string_lossy().to_string()
+    } else {
+        format!("{}/{}", prefix, component.to_string_lossy())
+    }
+}
+
+fn collect_leaves(node: &Node, prefix: &mut PathBuf, out: &mut Vec<(PathBuf, String)>) {
+    if node.children.is_empty() {
+        if let Some(hash) = &node.hash {
+            out.push((prefix.clone(), hash.clone()));
+        }
+        return;
+    }
+
+    for (name, child) in &node.children {
+        prefix.push(name);
+        collect_leaves(child, prefix, out);
+        prefix.pop();
+    }
+}
+
+fn diff_rec(a: &Node, b: &Node, prefix: &mut PathBuf, out: &mut Vec<PathBuf>) {
+    if a.hash == b.hash {
+        return; // identical subtree - nothing beneath it changed
+    }
+
+    if a.children.is_empty() && b.children.is_empty() {
+        out.push(prefix.clone());
+        return;
+    }
+
+    let mut names: Vec<&OsString> = a.children.keys().chain(b.children.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    for name in names {
+        prefix.push(name);
+        match (a.children.get(name), b.children.get(name)) {
+            (Some(ca), Some(cb)) => diff_rec(ca, cb, prefix, out),
+            (Some(ca), None) => collect_paths(ca, prefix, out),
+            (None, Some(cb)) => collect_paths(cb, prefix, out),
+            (None, None) => unreachable!("name came from one of the two child maps"),
+        }
+        prefix.pop();
+    }
+}
+
+/// Every leaf path beneath `node` (inclusive of `node` itself if it's a
+/// leaf), used by `diff_rec` for a subtree that exists on only one side.
+fn collect_paths(node: &Node, prefix: &mut PathBuf, out: &mut Vec<PathBuf>) {
+    if node.children.is_empty() {
+        out.push(prefix.clone());
+        return;
+    }
+
+    for (name, child) in &node.children {
+        prefix.push(name);
+        collect_paths(child, prefix, out);
+        prefix.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_updates_root_hash() {
+        let mut tree = MerkleTree::new();
+        assert_eq!(tree.root_hash(), None);
+
+        tree.insert(Path::new("a/b.rs"), "hash1".to_string());
+        let first_root = tree.root_hash();
+        assert!(first_root.is_some());
+
+        tree.insert(Path::new("a/b.rs"), "hash2".to_string());
+        assert_ne!(tree.root_hash(), first_root);
+    }
+
+    #[test]
+    fn test_unrelated_subtree_unaffected_by_sibling_change() {
+        let mut tree = MerkleTree::new();
+        tree.insert(Path::new("pkg-a/src/lib.rs"), "a1".to_string());
+        tree.insert(Path::new("pkg-b/src/lib.rs"), "b1".to_string());
+
+        let pkg_a_hash = tree.get(Path::new("pkg-a")).unwrap();
+        tree.insert(Path::new("pkg-b/src/lib.rs"), "b2".to_string());
+
+        assert_eq!(tree.get(Path::new("pkg-a")), Some(pkg_a_hash));
+    }
+
+    #[test]
+    fn test_remove_prunes_empty_ancestors() {
+        let mut tree = MerkleTree::new();
+        tree.insert(Path::new("pkg/only-file.rs"), "h".to_string());
+        assert!(tree.get(Path::new("pkg")).is_some());
+
+        tree.remove(Path::new("pkg/only-file.rs"));
+        assert_eq!(tree.get(Path::new("pkg")), None);
+        assert_eq!(tree.root_hash(), None);
+    }
+
+    #[test]
+    fn test_root_hash_independent_of_insertion_order() {
+        let mut a = MerkleTree::new();
+        a.insert(Path::new("x/1.rs"), "h1".to_string());
+        a.insert(Path::new("x/2.rs"), "h2".to_string());
+
+        let mut b = MerkleTree::new();
+        b.insert(Path::new("x/2.rs"), "h2".to_string());
+        b.insert(Path::new("x/1.rs"), "h1".to_string());
+
+        assert_eq!(a.root_hash(), b.root_hash());
+    }
+
+    #[test]
+    fn test_diff_finds_changed_leaf_only() {
+        let mut a = MerkleTree::new();
+        a.insert(Path::new("pkg-a/lib.rs"), "a1".to_string());
+        a.insert(Path::new("pkg-b/lib.rs"), "b1".to_string());
+
+        let mut b = a.clone();
+        b.insert(Path::new("pkg-b/lib.rs"), "b2".to_string());
+
+        let changed = a.diff(&b);
+        assert_eq!(changed, vec![PathBuf::from("pkg-b/lib.rs")]);
+    }
+
+    #[test]
+    fn test_leaves_round_trip() {
+        let mut tree = MerkleTree::new();
+        tree.insert(Path::new("a/b.rs"), "h1".to_string());
+        tree.insert(Path::new("a/c.rs"), "h2".to_string());
+
+        let mut leaves = tree.leaves();
+        leaves.sort();
+        assert_eq!(
+            leaves,
+            vec![
+                (PathBuf::from("a/b.rs"), "h1".to_string()),
+                (PathBuf::from("a/c.rs"), "h2".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Reporter - decouples progress observation from execution
+//!
+//! Before this, the only way to watch a run live was a TUI reading progress
+//! state the runner poked directly, which meant a non-interactive CI run had
+//! no equivalent output path at all. `Reporter` is the single callback
+//! surface a task runner emits to; a TUI implementation updates its own
+//! state from the callbacks, and [`CiReporter`] prints line-buffered,
+//! timestamped output instead, for when stdout isn't a TTY. This mirrors the
+//! reporter/console split mature task runners (moon, Bazel) use to keep one
+//! execution path driving multiple kinds of output.
+
+use crate::scheduler::{TaskResult, TaskStatus};
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+/// Progress sink a task runner emits to. Every method has a no-op default so
+/// an implementation only needs to override the callbacks it cares about.
+pub trait Reporter: Send + Sync {
+    fn on_task_start(&self, _task: &str) {}
+    fn on_task_log(&self, _task: &str, _line: &str, _is_stderr: bool) {}
+    fn on_task_finish(&self, _result: &TaskResult) {}
+    fn on_run_summary(&self, _summary: &RunSummary) {}
+}
+
+/// Aggregate stats for a finished run, passed to [`Reporter::on_run_summary`].
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub ran: usize,
+    pub cached: usize,
+    pub failed: usize,
+    pub total_duration: Duration,
+}
+
+/// Line-buffered, timestamped, prefixed reporter for non-interactive runs
+/// (CI logs, output redirected to a file) where there's no terminal for a
+/// TUI to draw into.
+pub struct CiReporter {
+    start: Instant,
+}
+
+impl CiReporter {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    /// Whether stdout isn't a TTY - the signal that a `CiReporter` is the
+    /// right choice instead of the interactive TUI.
+    pub fn should_use() -> bool {
+        !std::io::stdout().is_terminal()
+    }
+
+    fn elapsed(&self) -> String {
+        format!("[{:>7.2}s]", self.start.elapsed().as_secs_f64())
+    }
+}
+
+impl Default for CiReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporter for CiReporter {
+    fn on_task_start(&self, task: &str) {
+        println!("{} ▶ {}", self.elapsed(), task);
+    }
+
+    fn on_task_log(&self, task: &str, line: &str, is_stderr: bool) {
+        if is_stderr {
+            eprintln!("{} {}: {}", self.elapsed(), task, line);
+        } else {
+            println!("{} {}: {}", self.elapsed(), task, line);
+        }
+    }
+
+    fn on_task_finish(&self, result: &TaskResult) {
+        let icon = match (result.status, result.cached) {
+            (TaskStatus::Completed, true) => "⚡",
+            (TaskStatus::Completed, false) => "✓",
+            (TaskStatus::Failed, _) => "✗",
+            (TaskStatus::Cancelled, _) => "⊘",
+            _ => "•",
+        };
+        println!(
+            "{} {} {} {}ms",
+            self.elapsed(),
+            icon,
+            result.name,
+            result.duration.as_millis()
+        );
+    }
+
+    fn on_run_summary(&self, summary: &RunSummary) {
+        println!(
+            "{} {} ran, {} cached, {} failed, total {:?}",
+            self.elapsed(),
+            summary.ran,
+            summary.cached,
+            summary.failed,
+            summary.total_duration
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopReporter;
+    impl Reporter for NoopReporter {}
+
+    #[test]
+    fn test_default_methods_are_callable_no_ops() {
+        let reporter = NoopReporter;
+        reporter.on_task_start("build");
+        reporter.on_task_log("build", "hello", false);
+        reporter.on_run_summary(&RunSummary {
+            ran: 1,
+            cached: 0,
+            failed: 0,
+            total_duration: Duration::from_millis(10),
+        });
+    }
+}
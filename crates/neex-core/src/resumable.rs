@@ -0,0 +1,151 @@
+//! Resumable Job State
+//!
+//! A `Scheduler` run has no memory of itself: kill the process mid-build and
+//! the next invocation redoes every task from scratch, cached outputs aside.
+//! [`JobState`] is a durable snapshot of one task's progress - written after
+//! every status transition and on shutdown - that a daemon can persist (see
+//! `neex-daemon`'s job store) and replay into a [`ResumeAction`] per task the
+//! next time a run starts. MessagePack (via `rmp-serde`) rather than JSON
+//! since this is written far more often than it's read and size/speed matter
+//! more than human-readability for a record nobody edits by hand.
+
+use crate::scheduler::TaskStatus;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Durable snapshot of one scheduler task, keyed by `task_name` in whatever
+/// store persists it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobState {
+    pub task_name: String,
+    pub status: TaskStatus,
+    /// The workspace `global_hash` this task was last scheduled against - a
+    /// resume is only valid while this still matches the current hash.
+    pub dependency_hash: String,
+    /// Output paths already captured for this task, so a job resumed as
+    /// `Completed` can be replayed without rerunning the action.
+    pub partial_outputs: Vec<String>,
+    /// How many steps of a multi-step task action already ran.
+    pub step_cursor: usize,
+}
+
+impl JobState {
+    pub fn new(task_name: impl Into<String>, dependency_hash: impl Into<String>) -> Self {
+        Self {
+            task_name: task_name.into(),
+            status: TaskStatus::Pending,
+            dependency_hash: dependency_hash.into(),
+            partial_outputs: Vec::new(),
+            step_cursor: 0,
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        Ok(rmp_serde::to_vec(self)?)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+/// What a previously-persisted [`JobState`] means for a fresh run, decided by
+/// comparing its `dependency_hash` against the workspace's current global
+/// hash. Critical invariant: a task whose upstream inputs changed is always
+/// `Discard`, never resumed - replaying its old outputs would be wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeAction {
+    /// Inputs unchanged and the job had already finished - skip straight to
+    /// `Completed` (or a cache hit) without re-running it.
+    Skip,
+    /// Inputs unchanged but the job was still in flight (`Pending`,
+    /// `Running`, or `Paused`) when its state was last persisted - rerun it
+    /// from `Pending`, since a single task action has no finer-grained
+    /// resume point than its own completion.
+    Restart,
+    /// Inputs changed, or the job ended in `Failed`/`Cancelled` - the record
+    /// is stale and dropped.
+    Discard,
+}
+
+/// One persisted job paired with the [`ResumeAction`] decided for it.
+#[derive(Debug, Clone)]
+pub struct ResumeEntry {
+    pub job: JobState,
+    pub action: ResumeAction,
+}
+
+/// Decide a [`ResumeAction`] for every persisted job against the workspace's
+/// current `global_hash`. Call this once on startup, before handing tasks to
+/// the scheduler, to build the resume plan.
+pub fn plan_resume(jobs: Vec<JobState>, current_global_hash: &str) -> Vec<ResumeEntry> {
+    jobs.into_iter()
+        .map(|job| {
+            let action = if job.dependency_hash != current_global_hash {
+                ResumeAction::Discard
+            } else {
+                match job.status {
+                    TaskStatus::Completed => ResumeAction::Skip,
+                    TaskStatus::Failed | TaskStatus::Cancelled => ResumeAction::Discard,
+                    TaskStatus::Pending | TaskStatus::Running | TaskStatus::Paused => {
+                        ResumeAction::Restart
+                    }
+                }
+            };
+            ResumeEntry { job, action }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completed_with_matching_hash_skips() {
+        let mut job = JobState::new("web:build", "abc123");
+        job.status = TaskStatus::Completed;
+        let plan = plan_resume(vec![job], "abc123");
+        assert_eq!(plan[0].action, ResumeAction::Skip);
+    }
+
+    #[test]
+    fn test_running_with_matching_hash_restarts() {
+        let mut job = JobState::new("web:build", "abc123");
+        job.status = TaskStatus::Running;
+        let plan = plan_resume(vec![job], "abc123");
+        assert_eq!(plan[0].action, ResumeAction::Restart);
+    }
+
+    #[test]
+    fn test_paused_with_matching_hash_restarts() {
+        let mut job = JobState::new("web:build", "abc123");
+        job.status = TaskStatus::Paused;
+        let plan = plan_resume(vec![job], "abc123");
+        assert_eq!(plan[0].action, ResumeAction::Restart);
+    }
+
+    #[test]
+    fn test_changed_hash_is_always_discarded() {
+        let mut job = JobState::new("web:build", "abc123");
+        job.status = TaskStatus::Completed;
+        let plan = plan_resume(vec![job], "def456");
+        assert_eq!(plan[0].action, ResumeAction::Discard);
+    }
+
+    #[test]
+    fn test_failed_job_is_discarded() {
+        let mut job = JobState::new("web:build", "abc123");
+        job.status = TaskStatus::Failed;
+        let plan = plan_resume(vec![job], "abc123");
+        assert_eq!(plan[0].action, ResumeAction::Discard);
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let job = JobState::new("web:build", "abc123");
+        let bytes = job.encode().unwrap();
+        let decoded = JobState::decode(&bytes).unwrap();
+        assert_eq!(job, decoded);
+    }
+}
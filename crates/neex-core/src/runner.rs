@@ -3,17 +3,21 @@
 //! The real Turbo-like feature:
 //! - Read package.json scripts
 //! - Execute with tokio::process
-//! - Persist cache to disk (sled DB)
+//! - Persist cache through a `CacheBackend` (local by default, remote when configured)
 //! - Replay cached output instantly on cache hit
 
+use crate::artifact_store::{ArtifactStore, Manifest};
+use crate::cache_backend::{self, CacheBackend};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::Mutex;
 
 /// Cached task output
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +28,9 @@ pub struct TaskOutput {
     pub duration_ms: u64,
     pub hash: String,
     pub cached_at: u64,
+    /// Captured output files (dist/, .next/, etc), restored on a cache hit
+    #[serde(default)]
+    pub outputs: Option<Manifest>,
 }
 
 /// Package.json structure (minimal)
@@ -31,24 +38,50 @@ pub struct TaskOutput {
 pub struct PackageJson {
     pub name: Option<String>,
     pub scripts: Option<HashMap<String, String>>,
+    /// Glob patterns (relative to the package root) of files each task
+    /// produces, e.g. `{"build": ["dist/**"]}`. Captured into the artifact
+    /// store on a cache miss and restored on a hit.
+    #[serde(default)]
+    pub outputs: Option<HashMap<String, Vec<String>>>,
 }
 
-/// Task Runner with persistent caching (sled DB)
+/// Cache freshness policy for `get_cached_fresh`, the way `bkt --ttl` works:
+/// an entry older than `ttl` is treated as a miss, and one older than
+/// `stale_after` (but still within `ttl`) is served immediately while a
+/// background task refreshes it for the next caller.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CachePolicy {
+    pub ttl: Option<Duration>,
+    pub stale_after: Option<Duration>,
+}
+
+/// Task Runner with persistent caching, backed by a pluggable `CacheBackend`
+#[derive(Clone)]
 pub struct TaskRunner {
     root: PathBuf,
-    db: sled::Db,
+    backend: Arc<dyn CacheBackend>,
+    artifacts: ArtifactStore,
+    /// Hashes with a background refresh in flight, so a burst of stale hits
+    /// triggers only one re-execution
+    refreshing: Arc<Mutex<HashSet<String>>>,
 }
 
 impl TaskRunner {
-    /// Create new task runner with persistent cache
+    /// Create new task runner with persistent cache, local by default. Set
+    /// `NEEX_CACHE_ADDR` (e.g. to an `http://` or `s3://bucket` address) to
+    /// share the cache with CI or other machines.
     pub fn new(root: impl AsRef<Path>) -> Result<Self> {
         let root = root.as_ref().to_path_buf();
-        let cache_dir = root.join(".neex").join("cache");
-        std::fs::create_dir_all(&cache_dir)?;
-
-        let db = sled::open(&cache_dir)?;
+        let addr = std::env::var("NEEX_CACHE_ADDR").unwrap_or_default();
+        let backend = cache_backend::from_addr(&addr, &root)?;
+        let artifacts = ArtifactStore::new(Arc::clone(&backend));
 
-        Ok(Self { root, db })
+        Ok(Self {
+            root,
+            backend,
+            artifacts,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+        })
     }
 
     /// Load package.json and get script command
@@ -64,9 +97,25 @@ impl TaskRunner {
         Ok(pkg.scripts.and_then(|s| s.get(script_name).cloned()))
     }
 
+    /// Glob patterns `task` declares as its outputs, empty if none declared
+    pub fn get_outputs(&self, task: &str) -> Result<Vec<String>> {
+        let pkg_path = self.root.join("package.json");
+        if !pkg_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = std::fs::read_to_string(&pkg_path)?;
+        let pkg: PackageJson = serde_json::from_str(&content)?;
+
+        Ok(pkg
+            .outputs
+            .and_then(|o| o.get(task).cloned())
+            .unwrap_or_default())
+    }
+
     /// Check if task is cached (persistent)
-    pub fn get_cached(&self, hash: &str) -> Result<Option<TaskOutput>> {
-        match self.db.get(hash.as_bytes())? {
+    pub async fn get_cached(&self, hash: &str) -> Result<Option<TaskOutput>> {
+        match self.backend.get(hash).await? {
             Some(data) => {
                 let output: TaskOutput = serde_json::from_slice(&data)?;
                 Ok(Some(output))
@@ -76,15 +125,93 @@ impl TaskRunner {
     }
 
     /// Store task output in persistent cache
-    pub fn store_cached(&self, hash: &str, output: &TaskOutput) -> Result<()> {
+    pub async fn store_cached(&self, hash: &str, output: &TaskOutput) -> Result<()> {
         let data = serde_json::to_vec(output)?;
-        self.db.insert(hash.as_bytes(), data)?;
-        self.db.flush()?;
-        Ok(())
+        self.backend.put(hash, data).await
+    }
+
+    /// Look up `hash` honoring `policy`: a miss once older than `ttl`, and a
+    /// background refresh (re-running `command`) kicked off once older than
+    /// `stale_after` while still returning the cached entry immediately.
+    pub async fn get_cached_fresh(
+        &self,
+        hash: &str,
+        command: &str,
+        policy: CachePolicy,
+    ) -> Result<Option<TaskOutput>> {
+        let Some(output) = self.get_cached(hash).await? else {
+            return Ok(None);
+        };
+
+        let age = Duration::from_secs(
+            now_secs().saturating_sub(output.cached_at),
+        );
+
+        if let Some(ttl) = policy.ttl {
+            if age > ttl {
+                return Ok(None);
+            }
+        }
+
+        if let Some(stale_after) = policy.stale_after {
+            if age > stale_after {
+                self.spawn_refresh(hash, command);
+            }
+        }
+
+        Ok(Some(output))
+    }
+
+    /// Re-run `command` in the background and overwrite the cache entry for
+    /// `hash`. No-op if a refresh for this hash is already in flight.
+    fn spawn_refresh(&self, hash: &str, command: &str) {
+        let runner = self.clone();
+        let hash = hash.to_string();
+        let command = command.to_string();
+
+        tokio::spawn(async move {
+            {
+                let mut refreshing = runner.refreshing.lock().await;
+                if !refreshing.insert(hash.clone()) {
+                    return;
+                }
+            }
+
+            let result = runner.execute(&command, None).await;
+            runner.refreshing.lock().await.remove(&hash);
+
+            match result {
+                Ok(mut output) => {
+                    output.hash = hash.clone();
+                    if let Err(e) = runner.store_cached(&hash, &output).await {
+                        tracing::warn!("failed to store refreshed cache entry {}: {}", hash, e);
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("background cache refresh failed for {}: {}", hash, e);
+                }
+            }
+        });
     }
 
-    /// Execute a task and capture output
-    pub async fn execute(&self, command: &str) -> Result<TaskOutput> {
+    /// Capture the files matched by `output_globs` (relative to `self.root`)
+    /// into the content-addressable blob store
+    pub async fn capture_outputs(&self, output_globs: &[String]) -> Result<Manifest> {
+        self.artifacts.capture(&self.root, output_globs).await
+    }
+
+    /// Materialize a cached task's output files back onto disk
+    pub async fn restore_outputs(&self, output: &TaskOutput) -> Result<()> {
+        match &output.outputs {
+            Some(manifest) => self.artifacts.restore(&self.root, manifest).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Execute a task and capture output, streaming each line to the console
+    /// as it arrives. `label` (e.g. a package name) is prefixed to every
+    /// streamed line so parallel runs across packages stay legible.
+    pub async fn execute(&self, command: &str, label: Option<&str>) -> Result<TaskOutput> {
         let start = Instant::now();
 
         // Use sh on Unix, cmd on Windows
@@ -110,7 +237,7 @@ impl TaskRunner {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
             while let Some(line) = lines.next_line().await? {
-                println!("{}", line); // Stream to console
+                print_prefixed(label, &line, false);
                 stdout_lines.push(line);
             }
         }
@@ -120,7 +247,7 @@ impl TaskRunner {
             let reader = BufReader::new(stderr);
             let mut lines = reader.lines();
             while let Some(line) = lines.next_line().await? {
-                eprintln!("{}", line); // Stream to console
+                print_prefixed(label, &line, true);
                 stderr_lines.push(line);
             }
         }
@@ -134,35 +261,45 @@ impl TaskRunner {
             exit_code: status.code().unwrap_or(-1),
             duration_ms: duration.as_millis() as u64,
             hash: String::new(),
-            cached_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs(),
+            cached_at: now_secs(),
+            outputs: None,
         })
     }
 
-    /// Replay cached output (print to console)
-    pub fn replay_output(&self, output: &TaskOutput) {
+    /// Replay cached output (print to console), same prefixing as `execute`
+    pub fn replay_output(&self, output: &TaskOutput, label: Option<&str>) {
         for line in &output.stdout {
-            println!("{}", line);
+            print_prefixed(label, line, false);
         }
         for line in &output.stderr {
-            eprintln!("{}", line);
+            print_prefixed(label, line, true);
         }
     }
 
     /// Get cache stats
-    pub fn cache_stats(&self) -> Result<(usize, u64)> {
-        let count = self.db.len();
-        let size = self.db.size_on_disk()?;
-        Ok((count, size))
+    pub async fn cache_stats(&self) -> Result<(usize, u64)> {
+        self.backend.stats().await
     }
 
     /// Clear cache
-    pub fn clear_cache(&self) -> Result<()> {
-        self.db.clear()?;
-        self.db.flush()?;
-        Ok(())
+    pub async fn clear_cache(&self) -> Result<()> {
+        self.backend.clear().await
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn print_prefixed(label: Option<&str>, line: &str, is_stderr: bool) {
+    match label {
+        Some(label) if is_stderr => eprintln!("  {} | {}", label, line),
+        Some(label) => println!("  {} | {}", label, line),
+        None if is_stderr => eprintln!("{}", line),
+        None => println!("{}", line),
     }
 }
 
@@ -173,7 +310,7 @@ mod tests {
     #[tokio::test]
     async fn test_execute_simple_command() {
         let runner = TaskRunner::new(".").unwrap();
-        let output = runner.execute("echo hello").await.unwrap();
+        let output = runner.execute("echo hello", None).await.unwrap();
 
         assert_eq!(output.exit_code, 0);
         assert!(output.stdout.iter().any(|l| l.contains("hello")));
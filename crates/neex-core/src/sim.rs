@@ -0,0 +1,157 @@
+//! Deterministic Simulation Runtime - reproducible scheduler fuzzing
+//!
+//! Concurrency bugs in `Scheduler::execute` (lock ordering around shared
+//! state, a task racing the fail-fast path, a lost wakeup) are nearly
+//! impossible to reproduce under real tokio timing - the interleaving that
+//! triggered them depends on wall-clock scheduling that's different every
+//! run. `Runtime` pulls task spawning and sleeping out from under `Scheduler`
+//! behind a small trait so dispatch can run against either:
+//! - [`TokioRuntime`], the production implementation, or
+//! - [`SimRuntime`], which drives the exact same dispatch logic but resolves
+//!   every spawn through a seeded jitter under a paused tokio clock, so the
+//!   order tasks finish in is a pure function of the seed - a failing seed
+//!   reproduces the same interleaving every time, and a fuzz loop can sweep
+//!   seeds looking for one that deadlocks or drops a result.
+//!
+//! This doesn't go as far as madsim's from-scratch single-threaded executor -
+//! it still runs on a real (single-threaded, paused-clock) tokio runtime -
+//! but it gets the property that matters for reproducing a flake: same seed
+//! in, same task-completion order out.
+
+use async_trait::async_trait;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+/// Abstracts the two things that make `Scheduler`'s dispatch loop
+/// non-reproducible under real tokio: when a spawned task actually gets to
+/// run, and how long a sleep (e.g. `RetryPolicy` backoff) takes.
+#[async_trait]
+pub trait Runtime: Send + Sync {
+    /// Run `fut` to completion as a background unit of work, waiting for one
+    /// of this runtime's concurrency slots to free up first.
+    fn spawn_permitted(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>);
+
+    /// Pause the calling task for `duration`.
+    async fn sleep(&self, duration: Duration);
+
+    /// Wait for every unit of work handed to `spawn_permitted` so far to finish.
+    async fn join_all(&self);
+}
+
+/// Production [`Runtime`]: a real `tokio::spawn` behind a `Semaphore`, real
+/// `tokio::time::sleep`. This is what `Scheduler` uses when no runtime is
+/// injected via `with_runtime`.
+pub struct TokioRuntime {
+    semaphore: Arc<Semaphore>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl TokioRuntime {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(concurrency.max(1))),
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Runtime for TokioRuntime {
+    fn spawn_permitted(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        let semaphore = Arc::clone(&self.semaphore);
+        let handle = tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.unwrap();
+            fut.await;
+        });
+        self.handles.lock().unwrap().push(handle);
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    async fn join_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+}
+
+/// Tiny dependency-free xorshift64* PRNG - good enough to derive a
+/// reproducible jitter sequence from a seed, not meant for anything
+/// security-sensitive.
+struct XorShift64(u64);
+
+impl XorShift64 {
+    fn new(seed: u64) -> Self {
+        Self(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+}
+
+/// Deterministic [`Runtime`] for scheduler simulation tests and fuzzers.
+/// Every `spawn_permitted` call draws its next jitter from the seeded PRNG
+/// before actually starting the task; run under a paused tokio clock
+/// (`#[tokio::test(start_paused = true)]`), those jitters resolve in the
+/// order they were scheduled, not in whatever order the real scheduler
+/// happens to wake tasks - so the exact same seed against the exact same
+/// task graph always finishes tasks in the same order, and a CI fuzz loop
+/// can sweep seeds to hunt for a deadlock or lost result.
+pub struct SimRuntime {
+    seed: Mutex<XorShift64>,
+    semaphore: Arc<Semaphore>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl SimRuntime {
+    pub fn new(seed: u64, concurrency: usize) -> Self {
+        Self {
+            seed: Mutex::new(XorShift64::new(seed)),
+            semaphore: Arc::new(Semaphore::new(concurrency.max(1))),
+            handles: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn next_jitter(&self) -> Duration {
+        let micros = self.seed.lock().unwrap().next() % 1_000;
+        Duration::from_micros(micros)
+    }
+}
+
+#[async_trait]
+impl Runtime for SimRuntime {
+    fn spawn_permitted(&self, fut: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        let semaphore = Arc::clone(&self.semaphore);
+        let jitter = self.next_jitter();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(jitter).await;
+            let _permit = semaphore.acquire().await.unwrap();
+            fut.await;
+        });
+        self.handles.lock().unwrap().push(handle);
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    async fn join_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.lock().unwrap());
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+}
@@ -1,308 +1,434 @@
-//! Symbol Graph - Phase 8.2
+//! Symbol Graph - symbol-level dependency tracking and invalidation
 //!
-//! Build a dependency graph at the Symbol level (not file level).
-//! This enables 10x reduction in rebuilds by tracking which files
-//! use which specific exports.
+//! `symbols` extracts per-file `FileSymbols` (hashed exports, raw imports) but
+//! stops at the file boundary. This module stitches those per-file extractions
+//! into a cross-file graph: each `Import.from` is resolved (relative paths,
+//! index resolution, bare package specifiers) to the file it targets, and each
+//! imported name is linked to the specific exported `Symbol` that satisfies it
+//! - following re-export chains (`export { a } from "./m"`, `export * from
+//!   "./m"`) through to the file that actually defines it.
 //!
-//! Example:
-//!   formatDate() changed → only rebuild files that import formatDate
-//!   (NOT all files that import the package)
-
+//! This is what makes symbol-level invalidation possible: a change to one
+//! function in a large file only dirties the symbols that changed, and
+//! `invalidate` walks the reverse-dependency edges from there to find the
+//! minimal set of rebuild units - renaming a local variable inside an
+//! unchanged exported function never cascades.
+
+use crate::import_graph::resolve_specifier;
+use crate::symbols::{extract_from_file, FileSymbols};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use serde::{Deserialize, Serialize};
-use crate::symbols::{extract_from_file, Symbol};
 
-/// Unique identifier for a symbol: "package:symbol_name"
-pub type SymbolId = String;
+/// Exported symbol name, scoped to whichever file it's keyed against
+pub type SymbolName = String;
 
-/// Symbol Graph - tracks symbol dependencies across files
-#[derive(Debug, Default)]
+/// Symbol-level dependency graph, built from already-extracted `FileSymbols`
 pub struct SymbolGraph {
-    /// Symbol -> files that import it
-    pub consumers: HashMap<SymbolId, HashSet<PathBuf>>,
-    
-    /// File -> its exported symbols with hashes
-    pub exports: HashMap<PathBuf, Vec<Symbol>>,
-    
-    /// Package name -> file path (for resolving imports)
-    pub packages: HashMap<String, PathBuf>,
-}
-
-/// Stored symbol hashes for change detection
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct SymbolCache {
-    /// Symbol ID -> hash
-    pub hashes: HashMap<SymbolId, String>,
+    files: HashMap<PathBuf, FileSymbols>,
+    /// (file, exported symbol) -> the (file, symbol) pairs that depend on it,
+    /// either by importing it directly or by re-exporting it onward
+    reverse_deps: HashMap<(PathBuf, SymbolName), Vec<(PathBuf, SymbolName)>>,
+    /// Bare specifier (package name) -> its resolved entry file, for imports
+    /// that aren't relative paths
+    packages: HashMap<String, PathBuf>,
 }
 
 impl SymbolGraph {
-    /// Build graph from workspace root
-    pub fn build(root: &Path) -> Result<Self> {
-        let mut graph = SymbolGraph::default();
-        
-        // Find all packages
-        graph.discover_packages(root)?;
-        
-        // Extract symbols from all JS/TS files
-        graph.extract_all_symbols(root)?;
-        
-        // Build consumer map
-        graph.build_consumers(root)?;
-        
-        Ok(graph)
+    /// Build the graph from already-extracted per-file symbols. Bare package
+    /// specifiers are resolved by scanning for `package.json` files under the
+    /// common ancestor of `files`.
+    pub fn build(files: HashMap<PathBuf, FileSymbols>) -> Self {
+        let packages = infer_root(&files)
+            .map(|root| discover_packages(&root))
+            .unwrap_or_default();
+
+        let mut graph = Self {
+            files,
+            reverse_deps: HashMap::new(),
+            packages,
+        };
+        graph.link();
+        graph
     }
 
-    /// Discover packages in workspace
-    fn discover_packages(&mut self, root: &Path) -> Result<()> {
-        let pkg_json = root.join("package.json");
-        if !pkg_json.exists() {
-            return Ok(());
+    /// Walk every parseable file under `root`, extract its symbols, and build
+    /// the graph from the result.
+    pub fn build_from_root(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let mut files = HashMap::new();
+
+        for entry in ignore::WalkBuilder::new(root)
+            .hidden(false)
+            .git_ignore(true)
+            .build()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false)
+                && crate::ast_hasher::is_parseable(path)
+            {
+                if let Ok(symbols) = extract_from_file(path) {
+                    files.insert(path.to_path_buf(), symbols);
+                }
+            }
         }
 
-        let content = std::fs::read_to_string(&pkg_json)?;
-        let pkg: serde_json::Value = serde_json::from_str(&content)?;
+        Ok(Self::build(files))
+    }
 
-        // Get workspaces
-        let workspaces = pkg.get("workspaces")
-            .and_then(|w| w.as_array())
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>())
-            .unwrap_or_default();
+    /// Resolve `specifier` as seen from `from` to a file present in the graph
+    fn resolve(&self, from: &Path, specifier: &str) -> Option<PathBuf> {
+        if specifier.starts_with('.') {
+            resolve_specifier(from, specifier)
+        } else {
+            self.packages.get(specifier).cloned()
+        }
+        .filter(|target| self.files.contains_key(target))
+    }
+
+    /// Populate `reverse_deps` from every import and re-export edge
+    fn link(&mut self) {
+        let files: Vec<PathBuf> = self.files.keys().cloned().collect();
+
+        for file in &files {
+            let Some(fs) = self.files.get(file) else { continue };
+
+            // Re-exports: this file's own exports that forward another file's names
+            for export in &fs.exports {
+                let Some(spec) = &export.reexport_from else { continue };
+                let Some(target) = self.resolve(file, spec) else { continue };
+
+                if export.name == "*" {
+                    // `export * from "./m"`: forwards every name `target` exports
+                    let names: Vec<String> = self
+                        .files
+                        .get(&target)
+                        .map(|t| t.exports.iter().map(|s| s.name.clone()).filter(|n| n != "*").collect())
+                        .unwrap_or_default();
+                    for name in names {
+                        self.add_edge(&target, &name, file, &name);
+                    }
+                } else {
+                    self.add_edge(&target, &export.name, file, &export.name);
+                }
+            }
+
+            // Imports: files that consume another file's exports directly
+            for import in &fs.imports {
+                let Some(target) = self.resolve(file, &import.from) else { continue };
 
-        for pattern in workspaces {
-            let pattern_path = root.join(pattern);
-            let _base = pattern_path.parent().unwrap_or(root);
-            
-            if let Ok(entries) = glob::glob(&pattern_path.to_string_lossy()) {
-                for entry in entries.flatten() {
-                    if entry.is_dir() {
-                        let pkg_json = entry.join("package.json");
-                        if pkg_json.exists() {
-                            if let Ok(content) = std::fs::read_to_string(&pkg_json) {
-                                if let Ok(pkg) = serde_json::from_str::<serde_json::Value>(&content) {
-                                    if let Some(name) = pkg.get("name").and_then(|n| n.as_str()) {
-                                        self.packages.insert(name.to_string(), entry.clone());
-                                    }
-                                }
-                            }
+                for symbol_name in &import.symbols {
+                    if let Some(alias) = symbol_name.strip_prefix("* as ") {
+                        // Namespace import: conservatively depend on every export
+                        let names: Vec<String> = self
+                            .files
+                            .get(&target)
+                            .map(|t| t.exports.iter().map(|s| s.name.clone()).filter(|n| n != "*").collect())
+                            .unwrap_or_default();
+                        let consumer_name = format!("* as {}", alias);
+                        for name in names {
+                            self.add_edge(&target, &name, file, &consumer_name);
                         }
+                    } else {
+                        self.add_edge(&target, symbol_name, file, symbol_name);
                     }
                 }
             }
         }
-
-        Ok(())
     }
 
-    /// Extract symbols from all JS/TS files
-    fn extract_all_symbols(&mut self, root: &Path) -> Result<()> {
-        for (_, pkg_path) in &self.packages.clone() {
-            self.extract_package_symbols(pkg_path)?;
-        }
-        
-        // Also scan root src if exists
-        let src_dir = root.join("src");
-        if src_dir.exists() {
-            self.scan_directory(&src_dir)?;
-        }
-        
-        Ok(())
+    fn add_edge(&mut self, target_file: &Path, target_name: &str, consumer_file: &Path, consumer_name: &str) {
+        self.reverse_deps
+            .entry((target_file.to_path_buf(), target_name.to_string()))
+            .or_default()
+            .push((consumer_file.to_path_buf(), consumer_name.to_string()));
     }
 
-    /// Extract symbols from a package
-    fn extract_package_symbols(&mut self, pkg_path: &Path) -> Result<()> {
-        let src = pkg_path.join("src");
-        if src.exists() {
-            self.scan_directory(&src)?;
-        }
-        
-        // Check index files
-        for index in &["index.ts", "index.tsx", "index.js", "index.jsx"] {
-            let path = pkg_path.join(index);
-            if path.exists() {
-                self.extract_file(&path)?;
-            }
-        }
-        
-        Ok(())
+    /// The real content hash of `(file, name)`, following re-export chains to
+    /// the file that actually defines it. `None` if the name isn't exported
+    /// (directly or via a wildcard re-export) from `file`.
+    fn effective_hash(&self, file: &Path, name: &str) -> Option<String> {
+        self.effective_hash_inner(file, name, &mut HashSet::new())
     }
 
-    /// Scan directory for JS/TS files
-    fn scan_directory(&mut self, dir: &Path) -> Result<()> {
-        if !dir.exists() {
-            return Ok(());
+    fn effective_hash_inner(
+        &self,
+        file: &Path,
+        name: &str,
+        visited: &mut HashSet<(PathBuf, String)>,
+    ) -> Option<String> {
+        if !visited.insert((file.to_path_buf(), name.to_string())) {
+            return None; // re-export cycle
         }
 
-        for entry in walkdir::WalkDir::new(dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_file() {
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mts" | "mjs") {
-                    self.extract_file(path)?;
+        let fs = self.files.get(file)?;
+
+        if let Some(export) = fs.exports.iter().find(|s| s.name == name) {
+            return match &export.reexport_from {
+                Some(spec) => {
+                    let target = self.resolve(file, spec)?;
+                    self.effective_hash_inner(&target, name, visited)
                 }
-            }
+                None if !export.hash.is_empty() => Some(export.hash.clone()),
+                None => None,
+            };
         }
-        
-        Ok(())
-    }
 
-    /// Extract symbols from a single file
-    fn extract_file(&mut self, path: &Path) -> Result<()> {
-        match extract_from_file(path) {
-            Ok(symbols) => {
-                self.exports.insert(path.to_path_buf(), symbols.exports);
+        // Not a direct export - maybe forwarded through `export * from "./m"`
+        for export in &fs.exports {
+            if export.name != "*" {
+                continue;
+            }
+            let Some(spec) = &export.reexport_from else { continue };
+            let Some(target) = self.resolve(file, spec) else { continue };
+            if let Some(hash) = self.effective_hash_inner(&target, name, visited) {
+                return Some(hash);
             }
-            Err(_) => {} // Skip unparseable files
         }
-        Ok(())
-    }
 
-    /// Build consumer map from imports
-    fn build_consumers(&mut self, root: &Path) -> Result<()> {
-        // Scan all files again for imports
-        for (_, pkg_path) in &self.packages.clone() {
-            self.scan_imports(pkg_path, root)?;
-        }
-        
-        let src = root.join("src");
-        if src.exists() {
-            self.scan_imports(&src, root)?;
-        }
-        
-        Ok(())
+        None
     }
 
-    /// Scan imports in a directory
-    fn scan_imports(&mut self, dir: &Path, _root: &Path) -> Result<()> {
-        if !dir.exists() {
-            return Ok(());
+    /// Effective hash of every exported symbol in the graph, keyed by
+    /// (defining file, name). Re-exports resolve to the originating file's hash.
+    pub fn effective_hashes(&self) -> HashMap<(PathBuf, SymbolName), String> {
+        let mut out = HashMap::new();
+        for (file, fs) in &self.files {
+            for export in &fs.exports {
+                if export.name == "*" {
+                    continue;
+                }
+                if let Some(hash) = self.effective_hash(file, &export.name) {
+                    out.insert((file.clone(), export.name.clone()), hash);
+                }
+            }
         }
+        out
+    }
 
-        for entry in walkdir::WalkDir::new(dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_file() {
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mts" | "mjs") {
-                    self.process_imports(path)?;
+    /// Re-extract `changed_files`, diff their exports' effective hashes against
+    /// the graph's current state, and propagate the change through
+    /// `reverse_deps` to find every symbol transitively affected. A renamed
+    /// local variable inside an unchanged exported function produces the same
+    /// hash, so it never enters the worklist.
+    pub fn invalidate(&mut self, changed_files: &[PathBuf]) -> HashSet<(PathBuf, SymbolName)> {
+        let mut dirty_roots = Vec::new();
+
+        for file in changed_files {
+            let names_before: HashSet<String> = self
+                .files
+                .get(file)
+                .map(|fs| fs.exports.iter().map(|s| s.name.clone()).collect())
+                .unwrap_or_default();
+            let old_hashes: HashMap<String, Option<String>> = names_before
+                .iter()
+                .map(|name| (name.clone(), self.effective_hash(file, name)))
+                .collect();
+
+            let new_fs = extract_from_file(file).unwrap_or_default();
+            let names_after: HashSet<String> = new_fs.exports.iter().map(|s| s.name.clone()).collect();
+            self.files.insert(file.clone(), new_fs);
+
+            for name in names_before.union(&names_after) {
+                let old_hash = old_hashes.get(name).cloned().flatten();
+                let new_hash = self.effective_hash(file, name);
+                if old_hash != new_hash {
+                    dirty_roots.push((file.clone(), name.clone()));
                 }
             }
         }
-        
-        Ok(())
+
+        // Re-exports and imports may now point at different targets, so relink
+        // once all changed files are re-extracted rather than per-file.
+        self.reverse_deps.clear();
+        self.link();
+
+        self.propagate(&dirty_roots)
     }
 
-    /// Process imports in a file
-    fn process_imports(&mut self, file: &Path) -> Result<()> {
-        let symbols = extract_from_file(file)?;
-        
-        for import in symbols.imports {
-            // Check if import is from a known package
-            if let Some(_pkg_path) = self.packages.get(&import.from) {
-                for symbol_name in &import.symbols {
-                    let id = format!("{}:{}", import.from, symbol_name);
-                    self.consumers
-                        .entry(id)
-                        .or_default()
-                        .insert(file.to_path_buf());
+    /// Worklist propagation from a set of dirty `(file, symbol)` roots to every
+    /// `(file, symbol)` transitively affected through `reverse_deps`.
+    fn propagate(&self, roots: &[(PathBuf, SymbolName)]) -> HashSet<(PathBuf, SymbolName)> {
+        let mut affected = HashSet::new();
+        let mut queue: Vec<(PathBuf, SymbolName)> = roots.to_vec();
+        let mut visited: HashSet<(PathBuf, SymbolName)> = HashSet::new();
+
+        while let Some(item) = queue.pop() {
+            if !visited.insert(item.clone()) {
+                continue;
+            }
+            if let Some(consumers) = self.reverse_deps.get(&item) {
+                for consumer in consumers {
+                    if affected.insert(consumer.clone()) {
+                        queue.push(consumer.clone());
+                    }
                 }
             }
         }
-        
-        Ok(())
-    }
 
-    /// Get files that import a specific symbol
-    pub fn get_consumers(&self, package: &str, symbol: &str) -> Vec<PathBuf> {
-        let id = format!("{}:{}", package, symbol);
-        self.consumers
-            .get(&id)
-            .map(|set| set.iter().cloned().collect())
-            .unwrap_or_default()
+        affected
     }
 
-    /// Get all exported symbols from all packages
-    pub fn get_all_symbols(&self) -> Vec<(SymbolId, String)> {
-        let mut result = Vec::new();
-        
-        for (path, symbols) in &self.exports {
-            // Find package name for this path
-            let pkg_name = self.packages.iter()
-                .find(|(_, p)| path.starts_with(p))
-                .map(|(name, _)| name.clone())
-                .unwrap_or_else(|| path.to_string_lossy().to_string());
-            
-            for symbol in symbols {
-                let id = format!("{}:{}", pkg_name, symbol.name);
-                result.push((id, symbol.hash.clone()));
+    /// Unique files touched by `affected`, in a stable dependency-respecting
+    /// order: a file always comes after every other affected file it imports.
+    pub fn rebuild_order(&self, affected: &HashSet<(PathBuf, SymbolName)>) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = affected
+            .iter()
+            .map(|(file, _)| file.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        files.sort();
+
+        let file_set: HashSet<&PathBuf> = files.iter().collect();
+        let mut indegree: HashMap<PathBuf, usize> = files.iter().map(|f| (f.clone(), 0)).collect();
+        let mut dependents: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+        for file in &files {
+            let Some(fs) = self.files.get(file) else { continue };
+            let mut seen_deps = HashSet::new();
+            for import in &fs.imports {
+                let Some(target) = self.resolve(file, &import.from) else { continue };
+                if file_set.contains(&target) && &target != file && seen_deps.insert(target.clone()) {
+                    dependents.entry(target).or_default().push(file.clone());
+                    *indegree.get_mut(file).unwrap() += 1;
+                }
             }
         }
-        
-        result
-    }
 
-    /// Get changed symbols by comparing with previous cache
-    pub fn get_changed_symbols(&self, cache: &SymbolCache) -> Vec<SymbolId> {
-        let mut changed = Vec::new();
-        
-        for (id, hash) in self.get_all_symbols() {
-            match cache.hashes.get(&id) {
-                Some(old_hash) if old_hash == &hash => {} // Same
-                _ => changed.push(id), // New or changed
+        let mut ready: std::collections::BTreeSet<PathBuf> =
+            indegree.iter().filter(|(_, &d)| d == 0).map(|(f, _)| f.clone()).collect();
+        let mut order = Vec::new();
+
+        while let Some(next) = ready.iter().next().cloned() {
+            ready.remove(&next);
+            order.push(next.clone());
+            if let Some(deps) = dependents.get(&next) {
+                for dep in deps {
+                    let entry = indegree.get_mut(dep).unwrap();
+                    *entry -= 1;
+                    if *entry == 0 {
+                        ready.insert(dep.clone());
+                    }
+                }
             }
         }
-        
-        changed
-    }
 
-    /// Get all files affected by changed symbols
-    pub fn get_affected_files(&self, changed: &[SymbolId]) -> Vec<PathBuf> {
-        let mut affected = HashSet::new();
-        
-        for id in changed {
-            if let Some(consumers) = self.consumers.get(id) {
-                affected.extend(consumers.iter().cloned());
+        // A dependency cycle leaves leftovers unordered by indegree; append them
+        // in the stable sort order already established above.
+        for file in files {
+            if !order.contains(&file) {
+                order.push(file);
             }
         }
-        
-        affected.into_iter().collect()
+
+        order
+    }
+
+    /// Symbols whose effective hash differs from what's recorded in `cache`,
+    /// for a one-shot comparison against a previous run's persisted state
+    /// (the daemon instead keeps the graph live and calls `invalidate`).
+    pub fn changed_since(&self, cache: &SymbolCache) -> HashSet<(PathBuf, SymbolName)> {
+        self.effective_hashes()
+            .into_iter()
+            .filter(|((file, name), hash)| cache.hashes.get(&cache_key(file, name)) != Some(hash))
+            .map(|(key, _)| key)
+            .collect()
     }
 
-    /// Create cache from current state
+    /// Snapshot of every symbol's effective hash, for persisting across runs
     pub fn to_cache(&self) -> SymbolCache {
-        let mut cache = SymbolCache::default();
-        
-        for (id, hash) in self.get_all_symbols() {
-            cache.hashes.insert(id, hash);
+        SymbolCache {
+            hashes: self
+                .effective_hashes()
+                .into_iter()
+                .map(|((file, name), hash)| (cache_key(&file, &name), hash))
+                .collect(),
         }
-        
-        cache
     }
 
-    /// Summary stats
+    /// (files, symbols, dependency edges)
     pub fn stats(&self) -> (usize, usize, usize) {
         (
-            self.packages.len(),
-            self.exports.values().map(|v| v.len()).sum(),
-            self.consumers.len(),
+            self.files.len(),
+            self.files.values().map(|fs| fs.exports.len()).sum(),
+            self.reverse_deps.values().map(|v| v.len()).sum(),
         )
     }
 }
 
+fn cache_key(file: &Path, name: &str) -> String {
+    format!("{}::{}", file.display(), name)
+}
+
+/// Longest common ancestor directory of every file in the graph, used to scope
+/// the `package.json` scan for bare-specifier resolution
+fn infer_root(files: &HashMap<PathBuf, FileSymbols>) -> Option<PathBuf> {
+    let mut iter = files.keys();
+    let first = iter.next()?;
+    let mut common: Vec<std::path::Component> = first.components().collect();
+    let first_len = common.len();
+
+    for path in iter {
+        let components: Vec<std::path::Component> = path.components().collect();
+        let shared = common.iter().zip(components.iter()).take_while(|(a, b)| a == b).count();
+        common.truncate(shared);
+    }
+
+    if common.len() == first_len {
+        common.pop(); // drop the file name itself, keep its directory
+    }
+    Some(common.into_iter().collect())
+}
+
+/// Scan for `package.json` files under `root` and map each package name to its
+/// entry file, for resolving bare import specifiers
+fn discover_packages(root: &Path) -> HashMap<String, PathBuf> {
+    let mut packages = HashMap::new();
+
+    for entry in ignore::WalkBuilder::new(root)
+        .hidden(false)
+        .git_ignore(true)
+        .build()
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_name() != "package.json" {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(entry.path()) else { continue };
+        let Ok(pkg) = serde_json::from_str::<serde_json::Value>(&content) else { continue };
+        let Some(name) = pkg.get("name").and_then(|n| n.as_str()) else { continue };
+        let Some(dir) = entry.path().parent() else { continue };
+        if let Some(entry_file) = find_entry_file(dir) {
+            packages.insert(name.to_string(), entry_file);
+        }
+    }
+
+    packages
+}
+
+fn find_entry_file(dir: &Path) -> Option<PathBuf> {
+    const CANDIDATES: &[&str] = &[
+        "index.ts", "index.tsx", "index.js", "index.jsx",
+        "src/index.ts", "src/index.tsx", "src/index.js", "src/index.jsx",
+    ];
+    CANDIDATES.iter().map(|c| dir.join(c)).find(|p| p.is_file())
+}
+
+/// Persisted, flattened symbol hashes from a previous run
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SymbolCache {
+    pub hashes: HashMap<String, String>,
+}
+
 impl SymbolCache {
-    /// Load from disk
     pub fn load(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path)?;
         Ok(serde_json::from_str(&content)?)
     }
 
-    /// Save to disk
     pub fn save(&self, path: &Path) -> Result<()> {
         let content = serde_json::to_string(self)?;
         if let Some(parent) = path.parent() {
@@ -319,81 +445,152 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
-    fn create_test_monorepo() -> tempfile::TempDir {
+    fn write(dir: &Path, rel: &str, content: &str) -> PathBuf {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_named_import_linked_to_export() {
+        let dir = tempdir().unwrap();
+        let a = write(dir.path(), "a.ts", "export function formatDate() { return 1; }");
+        let b = write(dir.path(), "b.ts", "import { formatDate } from './a';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+
+        let graph = SymbolGraph::build(files);
+        let affected = graph.propagate(&[(a, "formatDate".to_string())]);
+        assert!(affected.contains(&(b, "formatDate".to_string())));
+    }
+
+    #[test]
+    fn test_unrelated_symbol_change_does_not_cascade() {
+        let dir = tempdir().unwrap();
+        let a = write(
+            dir.path(),
+            "a.ts",
+            "export function used() { return 1; }\nexport function unused() { return 2; }",
+        );
+        let b = write(dir.path(), "b.ts", "import { used } from './a';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+
+        let graph = SymbolGraph::build(files);
+        let affected = graph.propagate(&[(a, "unused".to_string())]);
+        assert!(affected.is_empty());
+    }
+
+    #[test]
+    fn test_reexport_propagates_hash_through_to_consumer() {
         let dir = tempdir().unwrap();
-        let root = dir.path();
-
-        // Root package.json
-        fs::write(root.join("package.json"), r#"
-            {"workspaces": ["packages/*"]}
-        "#).unwrap();
-
-        // Utils package
-        let utils = root.join("packages/utils");
-        fs::create_dir_all(&utils).unwrap();
-        fs::write(utils.join("package.json"), r#"{"name": "@my/utils"}"#).unwrap();
-        fs::write(utils.join("index.ts"), r#"
-            export function formatDate() { return "date"; }
-            export function formatNumber() { return 123; }
-        "#).unwrap();
-
-        // Web package
-        let web = root.join("packages/web");
-        fs::create_dir_all(&web).unwrap();
-        fs::write(web.join("package.json"), r#"{"name": "@my/web"}"#).unwrap();
-        fs::write(web.join("index.ts"), r#"
-            import { formatDate } from "@my/utils";
-            export function App() { return formatDate(); }
-        "#).unwrap();
-
-        dir
+        let a = write(dir.path(), "a.ts", "export function helper() { return 1; }");
+        let b = write(dir.path(), "b.ts", "export { helper } from './a';");
+        let c = write(dir.path(), "c.ts", "import { helper } from './b';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+        files.insert(c.clone(), extract_from_file(&c).unwrap());
+
+        let graph = SymbolGraph::build(files);
+
+        // b's re-export resolves to a's real hash, not the empty placeholder
+        assert_eq!(
+            graph.effective_hash(&b, "helper"),
+            graph.effective_hash(&a, "helper")
+        );
+
+        let affected = graph.propagate(&[(a.clone(), "helper".to_string())]);
+        assert!(affected.contains(&(b, "helper".to_string())));
+        assert!(affected.contains(&(c, "helper".to_string())));
     }
 
     #[test]
-    fn test_build_graph() {
-        let dir = create_test_monorepo();
-        let graph = SymbolGraph::build(dir.path()).unwrap();
-        
-        assert_eq!(graph.packages.len(), 2);
-        assert!(graph.packages.contains_key("@my/utils"));
-        assert!(graph.packages.contains_key("@my/web"));
+    fn test_wildcard_reexport_forwards_every_name() {
+        let dir = tempdir().unwrap();
+        let a = write(
+            dir.path(),
+            "a.ts",
+            "export function foo() { return 1; }\nexport function bar() { return 2; }",
+        );
+        let b = write(dir.path(), "b.ts", "export * from './a';");
+        let c = write(dir.path(), "c.ts", "import { bar } from './b';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+        files.insert(c.clone(), extract_from_file(&c).unwrap());
+
+        let graph = SymbolGraph::build(files);
+        let affected = graph.propagate(&[(a, "bar".to_string())]);
+        assert!(affected.contains(&(c, "bar".to_string())));
     }
 
     #[test]
-    fn test_get_consumers() {
-        let dir = create_test_monorepo();
-        let graph = SymbolGraph::build(dir.path()).unwrap();
-        
-        let consumers = graph.get_consumers("@my/utils", "formatDate");
-        assert_eq!(consumers.len(), 1);
-        
-        let consumers = graph.get_consumers("@my/utils", "formatNumber");
-        assert_eq!(consumers.len(), 0); // Not imported anywhere
+    fn test_invalidate_detects_changed_export_hash() {
+        let dir = tempdir().unwrap();
+        let a = write(dir.path(), "a.ts", "export function formatDate() { return 1; }");
+        let b = write(dir.path(), "b.ts", "import { formatDate } from './a';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+
+        let mut graph = SymbolGraph::build(files);
+
+        write(dir.path(), "a.ts", "export function formatDate() { return 2; }");
+        let affected = graph.invalidate(&[a.clone()]);
+
+        assert!(affected.contains(&(b, "formatDate".to_string())));
     }
 
     #[test]
-    fn test_symbol_cache() {
-        let dir = create_test_monorepo();
-        let graph = SymbolGraph::build(dir.path()).unwrap();
-        let cache = graph.to_cache();
-        
-        // Should have symbols from utils
-        assert!(cache.hashes.keys().any(|k| k.contains("formatDate")));
+    fn test_invalidate_ignores_untouched_file() {
+        let dir = tempdir().unwrap();
+        let a = write(dir.path(), "a.ts", "export function formatDate() { return 1; }");
+        let b = write(dir.path(), "b.ts", "import { formatDate } from './a';");
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+
+        let mut graph = SymbolGraph::build(files);
+
+        // Re-running invalidate without touching the file on disk should never
+        // find a hash difference.
+        let affected = graph.invalidate(&[a]);
+        assert!(affected.is_empty());
     }
 
     #[test]
-    fn test_changed_detection() {
-        let dir = create_test_monorepo();
-        let graph = SymbolGraph::build(dir.path()).unwrap();
-        
-        // First run - everything is "changed"
-        let cache = SymbolCache::default();
-        let changed = graph.get_changed_symbols(&cache);
-        assert!(!changed.is_empty());
-        
-        // Second run with same cache - nothing changed
-        let cache = graph.to_cache();
-        let changed = graph.get_changed_symbols(&cache);
-        assert!(changed.is_empty());
+    fn test_rebuild_order_respects_dependencies() {
+        let dir = tempdir().unwrap();
+        let a = write(dir.path(), "a.ts", "export function foo() { return 1; }");
+        let b = write(
+            dir.path(),
+            "b.ts",
+            "import { foo } from './a';\nexport function bar() { return foo(); }",
+        );
+
+        let mut files = HashMap::new();
+        files.insert(a.clone(), extract_from_file(&a).unwrap());
+        files.insert(b.clone(), extract_from_file(&b).unwrap());
+
+        let graph = SymbolGraph::build(files);
+        let affected: HashSet<(PathBuf, SymbolName)> =
+            [(a.clone(), "foo".to_string()), (b.clone(), "bar".to_string())].into_iter().collect();
+
+        let order = graph.rebuild_order(&affected);
+        let a_pos = order.iter().position(|f| f == &a).unwrap();
+        let b_pos = order.iter().position(|f| f == &b).unwrap();
+        assert!(a_pos < b_pos);
     }
 }
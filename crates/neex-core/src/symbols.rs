@@ -30,6 +30,11 @@ pub struct Symbol {
     pub kind: SymbolKind,
     pub hash: String,
     pub line: usize,
+    /// Set for re-exports (`export { a } from "./m"`, `export * from "./m"`) to
+    /// the module specifier being re-exported. The hash is empty in this case;
+    /// consumers that care about the real content hash (e.g. `SymbolGraph`) must
+    /// follow this to the original exporting file.
+    pub reexport_from: Option<String>,
 }
 
 /// Import statement
@@ -80,23 +85,43 @@ pub fn extract_symbols(source: &str, is_typescript: bool) -> Result<FileSymbols>
 /// Extract exported symbols
 fn extract_exports(symbols: &mut FileSymbols, root: tree_sitter::Node, source: &[u8]) -> Result<()> {
     let mut cursor = root.walk();
-    
+
     for node in root.children(&mut cursor) {
         // export function name() { ... }
         // export const name = ...
         // export class Name { ... }
         if node.kind() == "export_statement" {
+            // `export { a } from "./m"` / `export * from "./m"` carry a source
+            // field alongside the usual declaration/clause fields.
+            let reexport_from = node
+                .child_by_field_name("source")
+                .map(|n| node_text(n, source).trim_matches(|c| c == '"' || c == '\'').to_string());
+
             if let Some(decl) = node.child_by_field_name("declaration") {
                 extract_declaration(symbols, decl, source)?;
             }
-            // Handle: export { a, b }
+            // Handle: export { a, b } [from "./m"]
             if let Some(clause) = node.child_by_field_name("value") {
                 if clause.kind() == "export_clause" {
-                    extract_export_clause(symbols, clause, source)?;
+                    extract_export_clause(symbols, clause, source, reexport_from.clone())?;
+                }
+            }
+
+            // export * from "./m" (no declaration, no export clause)
+            if let Some(from) = reexport_from {
+                let mut star_cursor = node.walk();
+                if node.children(&mut star_cursor).any(|c| c.kind() == "*") {
+                    symbols.exports.push(Symbol {
+                        name: "*".to_string(),
+                        kind: SymbolKind::Variable,
+                        hash: String::new(),
+                        line: node.start_position().row + 1,
+                        reexport_from: Some(from),
+                    });
                 }
             }
         }
-        
+
         // export default function() { ... }
         if node.kind() == "export_default_declaration" {
             if let Some(child) = node.child(1) {
@@ -107,17 +132,18 @@ fn extract_exports(symbols: &mut FileSymbols, root: tree_sitter::Node, source: &
                     _ => SymbolKind::Variable,
                 };
                 let hash = hash_node(child, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind,
                     hash,
                     line: child.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
     }
-    
+
     Ok(())
 }
 
@@ -134,24 +160,26 @@ fn extract_declaration(symbols: &mut FileSymbols, node: tree_sitter::Node, sourc
                     kind: SymbolKind::Function,
                     hash,
                     line: node.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
-        
+
         "class_declaration" => {
             if let Some(name_node) = node.child_by_field_name("name") {
                 let name = node_text(name_node, source);
                 let hash = hash_node(node, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind: SymbolKind::Class,
                     hash,
                     line: node.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
-        
+
         "lexical_declaration" | "variable_declaration" => {
             // const/let/var declarations
             let mut cursor = node.walk();
@@ -160,56 +188,60 @@ fn extract_declaration(symbols: &mut FileSymbols, node: tree_sitter::Node, sourc
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let name = node_text(name_node, source);
                         let hash = hash_node(child, source);
-                        
+
                         symbols.exports.push(Symbol {
                             name,
                             kind: SymbolKind::Const,
                             hash,
                             line: child.start_position().row + 1,
+                            reexport_from: None,
                         });
                     }
                 }
             }
         }
-        
+
         "type_alias_declaration" => {
             if let Some(name_node) = node.child_by_field_name("name") {
                 let name = node_text(name_node, source);
                 let hash = hash_node(node, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind: SymbolKind::Type,
                     hash,
                     line: node.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
-        
+
         "interface_declaration" => {
             if let Some(name_node) = node.child_by_field_name("name") {
                 let name = node_text(name_node, source);
                 let hash = hash_node(node, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind: SymbolKind::Interface,
                     hash,
                     line: node.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
-        
+
         "enum_declaration" => {
             if let Some(name_node) = node.child_by_field_name("name") {
                 let name = node_text(name_node, source);
                 let hash = hash_node(node, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind: SymbolKind::Enum,
                     hash,
                     line: node.start_position().row + 1,
+                    reexport_from: None,
                 });
             }
         }
@@ -220,25 +252,33 @@ fn extract_declaration(symbols: &mut FileSymbols, node: tree_sitter::Node, sourc
     Ok(())
 }
 
-/// Extract from: export { a, b, c }
-fn extract_export_clause(symbols: &mut FileSymbols, node: tree_sitter::Node, source: &[u8]) -> Result<()> {
+/// Extract from: export { a, b, c } [from "./m"]. `reexport_from` is `Some`
+/// when this clause re-exports names from another module instead of the
+/// module's own local bindings.
+fn extract_export_clause(
+    symbols: &mut FileSymbols,
+    node: tree_sitter::Node,
+    source: &[u8],
+    reexport_from: Option<String>,
+) -> Result<()> {
     let mut cursor = node.walk();
-    
+
     for child in node.children(&mut cursor) {
         if child.kind() == "export_specifier" {
             if let Some(name_node) = child.child_by_field_name("name") {
                 let name = node_text(name_node, source);
-                
+
                 symbols.exports.push(Symbol {
                     name,
                     kind: SymbolKind::Variable, // Could be anything
                     hash: String::new(), // No body to hash
                     line: child.start_position().row + 1,
+                    reexport_from: reexport_from.clone(),
                 });
             }
         }
     }
-    
+
     Ok(())
 }
 
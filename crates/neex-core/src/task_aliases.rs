@@ -0,0 +1,172 @@
+//! Task Aliases & Composite Pipelines
+//!
+//! Cargo-style aliases for tasks, declared in `.neex/tasks.json` at the
+//! workspace root: `{"b": "build", "ci": ["lint", "test", "build"]}`. A
+//! single-string alias resolves to one target task name; a list alias is a
+//! composite pipeline - an ordered sequence of stages the caller runs one at
+//! a time, short-circuiting on the first failure. Aliases may reference
+//! other aliases; `resolve_alias` walks that chain and rejects a cycle
+//! instead of recursing forever.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::graph::WorkspaceNode;
+
+/// One alias target: either a single task name or an ordered pipeline of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TaskAlias {
+    Single(String),
+    Pipeline(Vec<String>),
+}
+
+/// Load `.neex/tasks.json` relative to `root`, an empty map if it doesn't exist
+pub fn load_task_aliases(root: &Path) -> Result<HashMap<String, TaskAlias>> {
+    let path = root.join(".neex").join("tasks.json");
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Expand `task` through `aliases` into an ordered sequence of real task
+/// names. A task that isn't an alias resolves to itself (a single-element
+/// sequence).
+pub fn resolve_alias(aliases: &HashMap<String, TaskAlias>, task: &str) -> Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    resolve(aliases, task, &mut seen)
+}
+
+fn resolve(
+    aliases: &HashMap<String, TaskAlias>,
+    task: &str,
+    seen: &mut HashSet<String>,
+) -> Result<Vec<String>> {
+    let Some(alias) = aliases.get(task) else {
+        return Ok(vec![task.to_string()]);
+    };
+
+    if !seen.insert(task.to_string()) {
+        return Err(anyhow!("cycle detected while expanding task alias '{}'", task));
+    }
+
+    let targets = match alias {
+        TaskAlias::Single(target) => vec![target.clone()],
+        TaskAlias::Pipeline(targets) => targets.clone(),
+    };
+
+    let mut resolved = Vec::new();
+    for target in targets {
+        resolved.extend(resolve(aliases, &target, seen)?);
+    }
+
+    // Only the current recursion path should count as "in progress" - once
+    // this branch finishes, the same alias referenced again by a sibling
+    // branch (or a later stage in the same pipeline) isn't a real cycle.
+    seen.remove(task);
+
+    Ok(resolved)
+}
+
+/// Error if any alias name is also a real script some package in the
+/// workspace declares - otherwise it's ambiguous whether `neex build` means
+/// the alias or that package's own `build` script.
+pub fn check_alias_collisions(
+    aliases: &HashMap<String, TaskAlias>,
+    packages: &[&WorkspaceNode],
+) -> Result<()> {
+    for name in aliases.keys() {
+        if packages.iter().any(|p| p.scripts.iter().any(|s| s == name)) {
+            return Err(anyhow!(
+                "task alias '{}' collides with a package.json script of the same name",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, TaskAlias)]) -> HashMap<String, TaskAlias> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_non_alias_resolves_to_itself() {
+        let aliases = HashMap::new();
+        assert_eq!(resolve_alias(&aliases, "build").unwrap(), vec!["build"]);
+    }
+
+    #[test]
+    fn test_single_alias_expands_to_target() {
+        let aliases = aliases(&[("b", TaskAlias::Single("build".to_string()))]);
+        assert_eq!(resolve_alias(&aliases, "b").unwrap(), vec!["build"]);
+    }
+
+    #[test]
+    fn test_pipeline_alias_expands_in_order() {
+        let aliases = aliases(&[(
+            "ci",
+            TaskAlias::Pipeline(vec!["lint".to_string(), "test".to_string(), "build".to_string()]),
+        )]);
+        assert_eq!(
+            resolve_alias(&aliases, "ci").unwrap(),
+            vec!["lint", "test", "build"]
+        );
+    }
+
+    #[test]
+    fn test_nested_alias_expands_transitively() {
+        let aliases = aliases(&[
+            ("ci", TaskAlias::Pipeline(vec!["b".to_string(), "test".to_string()])),
+            ("b", TaskAlias::Single("build".to_string())),
+        ]);
+        assert_eq!(resolve_alias(&aliases, "ci").unwrap(), vec!["build", "test"]);
+    }
+
+    #[test]
+    fn test_cycle_is_rejected() {
+        let aliases = aliases(&[
+            ("a", TaskAlias::Single("b".to_string())),
+            ("b", TaskAlias::Single("a".to_string())),
+        ]);
+        assert!(resolve_alias(&aliases, "a").is_err());
+    }
+
+    #[test]
+    fn test_alias_referenced_twice_in_same_pipeline_is_not_a_cycle() {
+        let aliases = aliases(&[
+            ("b", TaskAlias::Single("build".to_string())),
+            (
+                "ci",
+                TaskAlias::Pipeline(vec!["b".to_string(), "test".to_string(), "b".to_string()]),
+            ),
+        ]);
+        assert_eq!(
+            resolve_alias(&aliases, "ci").unwrap(),
+            vec!["build", "test", "build"]
+        );
+    }
+
+    #[test]
+    fn test_collision_with_package_script_errors() {
+        let aliases = aliases(&[("build", TaskAlias::Single("compile".to_string()))]);
+        let node = WorkspaceNode {
+            name: "pkg".to_string(),
+            path: "pkg".into(),
+            package_json_path: "pkg/package.json".into(),
+            version: None,
+            scripts: vec!["build".to_string()],
+        };
+        let packages = vec![&node];
+        assert!(check_alias_collisions(&aliases, &packages).is_err());
+    }
+}
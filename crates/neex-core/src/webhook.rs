@@ -0,0 +1,254 @@
+//! Webhook Event Emitter
+//!
+//! Teams want build results in a dashboard or chat channel without gluing a
+//! shell script to every CI job, the way moon's webhook reporter works.
+//! [`WebhookEmitter`], configured by `CloudConfig.webhook`, queues
+//! `task.started`/`task.finished`/`cache.hit`/`cache.miss`/`run.completed`
+//! events as `run_task` and `run_all` produce them and batches them onto a
+//! background task so a slow or unreachable endpoint never blocks the build.
+//! Each batch is signed with an HMAC-SHA256 of the shared secret so the
+//! receiver can verify it actually came from this build.
+
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::cloud::load_config;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long to let events accumulate before POSTing a batch
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+/// Flush immediately once a batch reaches this many events rather than
+/// waiting out the rest of the interval
+const MAX_BATCH: usize = 20;
+
+/// Webhook destination, stored alongside `S3Config` in `CloudConfig`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub secret: String,
+}
+
+/// One build-lifecycle event, POSTed as part of a JSON array batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub event: String,
+    pub task: Option<String>,
+    pub status: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub cache_tier: Option<String>,
+    pub ok_count: Option<usize>,
+    pub fail_count: Option<usize>,
+    pub timestamp: u64,
+}
+
+impl WebhookEvent {
+    pub fn task_started(task: &str) -> Self {
+        Self {
+            event: "task.started".to_string(),
+            task: Some(task.to_string()),
+            status: None,
+            duration_ms: None,
+            cache_tier: None,
+            ok_count: None,
+            fail_count: None,
+            timestamp: now_secs(),
+        }
+    }
+
+    pub fn task_finished(task: &str, status: &str, duration_ms: u64, cache_tier: Option<&str>) -> Self {
+        Self {
+            event: "task.finished".to_string(),
+            task: Some(task.to_string()),
+            status: Some(status.to_string()),
+            duration_ms: Some(duration_ms),
+            cache_tier: cache_tier.map(str::to_string),
+            ok_count: None,
+            fail_count: None,
+            timestamp: now_secs(),
+        }
+    }
+
+    pub fn cache_hit(task: &str, cache_tier: &str) -> Self {
+        Self {
+            event: "cache.hit".to_string(),
+            task: Some(task.to_string()),
+            status: None,
+            duration_ms: None,
+            cache_tier: Some(cache_tier.to_string()),
+            ok_count: None,
+            fail_count: None,
+            timestamp: now_secs(),
+        }
+    }
+
+    pub fn cache_miss(task: &str) -> Self {
+        Self {
+            event: "cache.miss".to_string(),
+            task: Some(task.to_string()),
+            status: None,
+            duration_ms: None,
+            cache_tier: None,
+            ok_count: None,
+            fail_count: None,
+            timestamp: now_secs(),
+        }
+    }
+
+    pub fn run_completed(ok_count: usize, fail_count: usize, duration_ms: u64) -> Self {
+        Self {
+            event: "run.completed".to_string(),
+            task: None,
+            status: Some(if fail_count == 0 { "completed" } else { "failed" }.to_string()),
+            duration_ms: Some(duration_ms),
+            cache_tier: None,
+            ok_count: Some(ok_count),
+            fail_count: Some(fail_count),
+            timestamp: now_secs(),
+        }
+    }
+}
+
+enum Cmd {
+    Event(WebhookEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Background-batched, HMAC-signed webhook sender
+pub struct WebhookEmitter {
+    tx: mpsc::UnboundedSender<Cmd>,
+    _handle: JoinHandle<()>,
+}
+
+impl WebhookEmitter {
+    /// Build an emitter from the current `CloudConfig`, `None` if no
+    /// webhook is configured. Spawns the background flush loop immediately.
+    pub fn try_from_config() -> Result<Option<Self>> {
+        let config = load_config()?;
+        Ok(config.webhook.map(Self::new))
+    }
+
+    pub fn new(config: WebhookConfig) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(run_flush_loop(config, rx));
+        Self { tx, _handle: handle }
+    }
+
+    /// Queue an event without blocking - a slow or down endpoint just grows
+    /// the buffer until the next flush, never the caller's build.
+    pub fn emit(&self, event: WebhookEvent) {
+        let _ = self.tx.send(Cmd::Event(event));
+    }
+
+    /// Block until every event queued so far has been POSTed (or given up
+    /// on) - call once, right before the process exits, so the background
+    /// task isn't killed mid-flush.
+    pub async fn shutdown(&self) {
+        let (tx, rx) = oneshot::channel();
+        if self.tx.send(Cmd::Flush(tx)).is_ok() {
+            let _ = rx.await;
+        }
+    }
+}
+
+async fn run_flush_loop(config: WebhookConfig, mut rx: mpsc::UnboundedReceiver<Cmd>) {
+    let client = reqwest::Client::new();
+    let mut buffer = Vec::new();
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+    interval.tick().await; // first tick fires immediately; nothing to flush yet
+
+    loop {
+        tokio::select! {
+            cmd = rx.recv() => {
+                match cmd {
+                    Some(Cmd::Event(event)) => {
+                        buffer.push(event);
+                        if buffer.len() >= MAX_BATCH {
+                            flush(&client, &config, &mut buffer).await;
+                        }
+                    }
+                    Some(Cmd::Flush(ack)) => {
+                        flush(&client, &config, &mut buffer).await;
+                        let _ = ack.send(());
+                    }
+                    None => {
+                        flush(&client, &config, &mut buffer).await;
+                        return;
+                    }
+                }
+            }
+            _ = interval.tick() => {
+                flush(&client, &config, &mut buffer).await;
+            }
+        }
+    }
+}
+
+async fn flush(client: &reqwest::Client, config: &WebhookConfig, buffer: &mut Vec<WebhookEvent>) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    let batch = std::mem::take(buffer);
+    let Ok(body) = serde_json::to_vec(&batch) else {
+        return;
+    };
+
+    let signature = match sign(&config.secret, &body) {
+        Ok(sig) => sig,
+        Err(e) => {
+            tracing::warn!("failed to sign webhook payload: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = client
+        .post(&config.url)
+        .header("X-Neex-Signature", format!("sha256={signature}"))
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+    {
+        tracing::warn!("webhook delivery failed: {}", e);
+    }
+}
+
+fn sign(secret: &str, body: &[u8]) -> Result<String> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("invalid webhook secret: {}", e))?;
+    mac.update(body);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_is_deterministic_and_hex_encoded() {
+        let a = sign("secret", b"payload").unwrap();
+        let b = sign("secret", b"payload").unwrap();
+        assert_eq!(a, b);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_sign_differs_for_different_secrets() {
+        let a = sign("secret-a", b"payload").unwrap();
+        let b = sign("secret-b", b"payload").unwrap();
+        assert_ne!(a, b);
+    }
+}
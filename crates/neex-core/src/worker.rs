@@ -0,0 +1,255 @@
+//! Background Worker Supervision
+//!
+//! The daemon used to be fire-and-forget: once a build kicked off a
+//! background cloud upload there was no way to see it was running, let
+//! alone pause or cancel it. [`Worker`] is a small state machine modeled on
+//! Garage's task manager - `Active` while doing something, `Idle` while
+//! waiting for more work, `Dead` once it's finished for good - and
+//! [`WorkerRegistry`] is where long-running jobs register themselves so a
+//! caller can list, pause, resume, and cancel them (`neex_daemon::server`
+//! does this over the same Unix socket the CLI uses for `Stats`).
+//!
+//! Lives in `neex_core` rather than `neex_daemon` because the worker that
+//! registers itself, `CloudCache::upload_background`, is in this crate (it
+//! reports through a [`WorkerReporter`]), and this crate can't depend on the
+//! daemon crate. `WorkerPool`'s task dispatch (`crate::scheduler`) does not
+//! register its tasks here.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Where a worker is in its lifecycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkerState {
+    /// Doing work right now
+    Active,
+    /// Alive but waiting for more work (or paused)
+    Idle,
+    /// Finished for good and won't run again, with the reason it stopped
+    /// (e.g. "completed", "cancelled", "too many errors")
+    Dead(String),
+}
+
+/// A supervised background job. Implementors drive their own `state()`
+/// forward as they work; `pause`/`resume`/`cancel` are requests the job
+/// should honor on its own schedule (e.g. at the next checkpoint), not
+/// guarantees it stops mid-step.
+pub trait Worker: Send + Sync {
+    /// Human-readable name shown by `neex workers`, e.g. "cloud-upload:abc123"
+    fn name(&self) -> &str;
+    fn state(&self) -> WorkerState;
+    /// Last progress message the job reported, if any
+    fn progress(&self) -> Option<String>;
+    fn error_count(&self) -> u32;
+    fn pause(&self);
+    fn resume(&self);
+    fn cancel(&self);
+    /// Push a new `(state, progress)` pair, for a worker whose real work
+    /// happens out-of-band of its own `state()`/`progress()` methods - e.g.
+    /// a stand-in registered on behalf of a job running in another process,
+    /// driven entirely by whatever reports back over IPC. A worker that
+    /// tracks its own state directly (the common case) doesn't need this.
+    fn report(&self, _state: WorkerState, _progress: Option<String>) {}
+}
+
+/// A way for code that has no local `Arc<dyn Worker>` - a job kicked off from
+/// the CLI, say, which runs in a different process than the registry it
+/// should show up in - to register itself and report progress anyway.
+/// `neex_cli` implements this over the same Unix socket `send_request` uses
+/// for everything else; callers that have no daemon to report to (or can't
+/// reach it) can just pass `None`.
+#[async_trait::async_trait]
+pub trait WorkerReporter: Send + Sync {
+    /// Register a new remote worker named `name` and return the id future
+    /// `report` calls should use
+    async fn register(&self, name: String) -> Result<u64>;
+    /// Push a `(state, progress)` update for a previously registered worker
+    async fn report(&self, id: u64, state: WorkerState, progress: Option<String>) -> Result<()>;
+}
+
+/// Point-in-time snapshot of one worker, the shape sent back over the
+/// Unix socket (a `dyn Worker` itself isn't `Serialize`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerInfo {
+    pub id: u64,
+    pub name: String,
+    pub state: WorkerState,
+    pub progress: Option<String>,
+    pub error_count: u32,
+}
+
+/// Registry of every worker currently known about, keyed by an id assigned
+/// at registration time.
+#[derive(Default)]
+pub struct WorkerRegistry {
+    workers: RwLock<HashMap<u64, Arc<dyn Worker>>>,
+    next_id: AtomicU64,
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new worker and return the id `neex workers` will refer to it by
+    pub fn register(&self, worker: Arc<dyn Worker>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        self.workers.write().unwrap().insert(id, worker);
+        id
+    }
+
+    /// Drop dead workers and stop tracking them
+    pub fn reap(&self) {
+        self.workers
+            .write()
+            .unwrap()
+            .retain(|_, w| !matches!(w.state(), WorkerState::Dead(_)));
+    }
+
+    /// Snapshot of every registered worker, for `DaemonRequest::ListWorkers`
+    pub fn list(&self) -> Vec<WorkerInfo> {
+        self.workers
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(&id, w)| WorkerInfo {
+                id,
+                name: w.name().to_string(),
+                state: w.state(),
+                progress: w.progress(),
+                error_count: w.error_count(),
+            })
+            .collect()
+    }
+
+    pub fn pause(&self, id: u64) -> Result<()> {
+        self.with_worker(id, Worker::pause)
+    }
+
+    pub fn resume(&self, id: u64) -> Result<()> {
+        self.with_worker(id, Worker::resume)
+    }
+
+    pub fn cancel(&self, id: u64) -> Result<()> {
+        self.with_worker(id, Worker::cancel)
+    }
+
+    /// Forward a `(state, progress)` report to a specific worker, e.g. for a
+    /// job running in another process that can only reach this registry over
+    /// IPC and has no local `Arc<dyn Worker>` of its own to drive directly.
+    pub fn report(&self, id: u64, state: WorkerState, progress: Option<String>) -> Result<()> {
+        self.with_worker(id, |w| w.report(state, progress))
+    }
+
+    fn with_worker(&self, id: u64, f: impl FnOnce(&dyn Worker)) -> Result<()> {
+        let workers = self.workers.read().unwrap();
+        let worker = workers.get(&id).ok_or_else(|| anyhow!("no worker with id {id}"))?;
+        f(worker.as_ref());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Mutex;
+
+    struct TestWorker {
+        name: String,
+        paused: AtomicBool,
+        cancelled: AtomicBool,
+        progress: Mutex<Option<String>>,
+    }
+
+    impl Worker for TestWorker {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn state(&self) -> WorkerState {
+            if self.cancelled.load(Ordering::SeqCst) {
+                WorkerState::Dead("cancelled".to_string())
+            } else if self.paused.load(Ordering::SeqCst) {
+                WorkerState::Idle
+            } else {
+                WorkerState::Active
+            }
+        }
+
+        fn progress(&self) -> Option<String> {
+            self.progress.lock().unwrap().clone()
+        }
+
+        fn error_count(&self) -> u32 {
+            0
+        }
+
+        fn pause(&self) {
+            self.paused.store(true, Ordering::SeqCst);
+        }
+
+        fn resume(&self) {
+            self.paused.store(false, Ordering::SeqCst);
+        }
+
+        fn cancel(&self) {
+            self.cancelled.store(true, Ordering::SeqCst);
+        }
+    }
+
+    fn test_worker(name: &str) -> Arc<TestWorker> {
+        Arc::new(TestWorker {
+            name: name.to_string(),
+            paused: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            progress: Mutex::new(Some("starting".to_string())),
+        })
+    }
+
+    #[test]
+    fn test_register_and_list() {
+        let registry = WorkerRegistry::new();
+        let id = registry.register(test_worker("upload:abc"));
+
+        let workers = registry.list();
+        assert_eq!(workers.len(), 1);
+        assert_eq!(workers[0].id, id);
+        assert_eq!(workers[0].state, WorkerState::Active);
+    }
+
+    #[test]
+    fn test_pause_resume_cancel() {
+        let registry = WorkerRegistry::new();
+        let id = registry.register(test_worker("upload:def"));
+
+        registry.pause(id).unwrap();
+        assert_eq!(registry.list()[0].state, WorkerState::Idle);
+
+        registry.resume(id).unwrap();
+        assert_eq!(registry.list()[0].state, WorkerState::Active);
+
+        registry.cancel(id).unwrap();
+        assert_eq!(registry.list()[0].state, WorkerState::Dead("cancelled".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_id_errors() {
+        let registry = WorkerRegistry::new();
+        assert!(registry.pause(999).is_err());
+    }
+
+    #[test]
+    fn test_reap_drops_dead_workers() {
+        let registry = WorkerRegistry::new();
+        let id = registry.register(test_worker("upload:ghi"));
+        registry.cancel(id).unwrap();
+
+        registry.reap();
+        assert!(registry.list().is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+//! Compact Cache-Availability Digest
+//!
+//! `fetch_from_network` used to ask every peer for a hash with no idea
+//! whether any of them actually had it - O(peers) round-trips per artifact
+//! that scale badly past a handful of machines. [`BloomFilter`] is a small,
+//! serializable digest of the hashes a peer's cache holds: cheap enough to
+//! fetch and cache per peer, and precise enough (tunable via its false
+//! positive rate) that `fetch_from_network` can skip a peer outright when
+//! the filter says it definitely doesn't have an artifact.
+
+use serde::{Deserialize, Serialize};
+
+/// A standard k-hashes/m-bits Bloom filter, keyed by BLAKE3 so it reuses the
+/// same hash the cache and artifact server already hash everything with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: usize,
+    num_hashes: usize,
+}
+
+impl BloomFilter {
+    /// Size a filter for `expected_items` entries at the given false
+    /// positive rate (e.g. `0.01` for 1%), so the bandwidth/precision
+    /// tradeoff is tunable instead of a fixed guess.
+    pub fn with_false_positive_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        let expected_items = expected_items.max(1);
+        let ln2_sq = std::f64::consts::LN_2 * std::f64::consts::LN_2;
+        let num_bits = (-(expected_items as f64) * false_positive_rate.ln() / ln2_sq)
+            .ceil()
+            .max(64.0) as usize;
+        let num_hashes = ((num_bits as f64 / expected_items as f64) * std::f64::consts::LN_2)
+            .round()
+            .max(1.0) as usize;
+
+        Self {
+            bits: vec![0u64; num_bits.div_ceil(64)],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Build a filter over `keys` at the given false positive rate.
+    pub fn from_keys<I, S>(keys: I, false_positive_rate: f64) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let keys: Vec<S> = keys.into_iter().collect();
+        let mut filter = Self::with_false_positive_rate(keys.len(), false_positive_rate);
+        for key in &keys {
+            filter.insert(key.as_ref());
+        }
+        filter
+    }
+
+    pub fn insert(&mut self, key: &str) {
+        for idx in self.bit_indices(key) {
+            self.bits[idx / 64] |= 1 << (idx % 64);
+        }
+    }
+
+    /// True if `key` is *probably* present - false positives are possible
+    /// (at the configured rate), false negatives never are.
+    pub fn might_contain(&self, key: &str) -> bool {
+        self.bit_indices(key)
+            .all(|idx| self.bits[idx / 64] & (1 << (idx % 64)) != 0)
+    }
+
+    fn bit_indices(&self, key: &str) -> impl Iterator<Item = usize> + '_ {
+        let digest = blake3::hash(key.as_bytes());
+        let bytes = digest.as_bytes();
+        let h1 = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(bytes[8..16].try_into().unwrap());
+        let num_bits = self.num_bits as u64;
+        (0..self.num_hashes)
+            .map(move |i| (h1.wrapping_add((i as u64).wrapping_mul(h2)) % num_bits) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inserted_keys_are_found() {
+        let filter = BloomFilter::from_keys(["a", "b", "c"], 0.01);
+        assert!(filter.might_contain("a"));
+        assert!(filter.might_contain("b"));
+        assert!(filter.might_contain("c"));
+    }
+
+    #[test]
+    fn test_absent_keys_are_usually_rejected() {
+        let present: Vec<String> = (0..100).map(|i| format!("present-{i}")).collect();
+        let filter = BloomFilter::from_keys(present, 0.01);
+
+        let false_positives = (0..1000)
+            .filter(|i| filter.might_contain(&format!("absent-{i}")))
+            .count();
+
+        // At a 1% configured rate, comfortably under 10% actual should hold.
+        assert!(false_positives < 100, "too many false positives: {false_positives}");
+    }
+}
@@ -0,0 +1,176 @@
+//! Multiplexed Peer Connection
+//!
+//! Every artifact pull used to open a fresh `reqwest::get` to the peer, and
+//! there was no channel for a peer to proactively tell anyone else what it
+//! had. [`PeerConnection`] is a single long-lived WebSocket between two
+//! daemons that carries several logical streams at once - artifact
+//! request/response, "I just cached hash X" push announcements, and
+//! heartbeats - each [`Frame`] tagged with a `stream_id` and a payload type
+//! so concurrent artifact transfers and control messages share the one
+//! socket without blocking each other, and `PeerManager` only has to pay
+//! connection setup cost once per peer instead of once per fetch.
+
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::bloom::BloomFilter;
+use crate::p2p::{DIGEST_FALSE_POSITIVE_RATE, PEER_HEADER};
+
+/// One multiplexed message: `stream_id` ties an `ArtifactResponse` back to
+/// the `ArtifactRequest` that asked for it. Push messages that don't expect
+/// a reply (`Announce`, `Heartbeat`'s initiating side) just use `0`.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Frame {
+    pub stream_id: u64,
+    pub payload: FramePayload,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum FramePayload {
+    ArtifactRequest { hash: String },
+    /// `None` means the peer doesn't have this artifact - same meaning as a
+    /// 404 from the old per-request HTTP endpoint.
+    ArtifactResponse { sealed: Option<Vec<u8>> },
+    /// Pushed unprompted whenever a peer caches something new, so the other
+    /// side can update its digest of that peer without waiting for the next
+    /// scheduled `/digest` refresh.
+    Announce { hash: String },
+    Heartbeat,
+    HeartbeatAck,
+}
+
+/// A single long-lived, multiplexed connection to one peer. Cheap to clone
+/// (it's an `Arc` internally via `PeerManager::connections`) and safe to
+/// share across concurrent `request_artifact` calls - each gets its own
+/// `stream_id` and waits on its own oneshot reply.
+pub struct PeerConnection {
+    outbound: mpsc::UnboundedSender<Frame>,
+    pending: Arc<StdMutex<HashMap<u64, oneshot::Sender<Option<Vec<u8>>>>>>,
+    next_stream_id: AtomicU64,
+}
+
+impl PeerConnection {
+    /// Dial `addr`'s `/ws` endpoint, proving our identity with the same
+    /// fingerprint header the old `/artifact/:hash` requests used, then spawn
+    /// the read/write halves as background tasks. `digests` is this
+    /// `PeerManager`'s own digest cache - an `Announce` pushed by the remote
+    /// peer is folded straight into `digests[peer_id]` so `fetch_from_network`
+    /// sees it without waiting for the next timed refresh.
+    pub async fn connect(
+        addr: SocketAddr,
+        my_fingerprint: String,
+        peer_id: String,
+        digests: Arc<RwLock<HashMap<String, BloomFilter>>>,
+    ) -> Result<Arc<Self>> {
+        let mut request = format!("ws://{addr}/ws").into_client_request()?;
+        request.headers_mut().insert(
+            PEER_HEADER,
+            my_fingerprint
+                .parse()
+                .map_err(|_| anyhow!("invalid fingerprint header value"))?,
+        );
+
+        let (ws_stream, _) = tokio_tungstenite::connect_async(request).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<Frame>();
+        let pending: Arc<StdMutex<HashMap<u64, oneshot::Sender<Option<Vec<u8>>>>>> =
+            Arc::new(StdMutex::new(HashMap::new()));
+
+        tokio::spawn(async move {
+            while let Some(frame) = outbound_rx.recv().await {
+                let Ok(text) = serde_json::to_string(&frame) else { continue };
+                if write.send(WsMessage::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let reader_pending = Arc::clone(&pending);
+        let reader_outbound = outbound_tx.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(msg)) = read.next().await {
+                let WsMessage::Text(text) = msg else { continue };
+                let Ok(frame) = serde_json::from_str::<Frame>(&text) else { continue };
+
+                match frame.payload {
+                    FramePayload::ArtifactResponse { sealed } => {
+                        if let Some(tx) = reader_pending.lock().unwrap().remove(&frame.stream_id) {
+                            let _ = tx.send(sealed);
+                        }
+                    }
+                    FramePayload::Announce { hash } => {
+                        let mut digests = digests.write().await;
+                        digests
+                            .entry(peer_id.clone())
+                            .or_insert_with(|| {
+                                BloomFilter::with_false_positive_rate(1, DIGEST_FALSE_POSITIVE_RATE)
+                            })
+                            .insert(&hash);
+                    }
+                    FramePayload::Heartbeat => {
+                        let ack = Frame { stream_id: frame.stream_id, payload: FramePayload::HeartbeatAck };
+                        let _ = reader_outbound.send(ack);
+                    }
+                    FramePayload::HeartbeatAck | FramePayload::ArtifactRequest { .. } => {}
+                }
+            }
+        });
+
+        Ok(Arc::new(Self {
+            outbound: outbound_tx,
+            pending,
+            next_stream_id: AtomicU64::new(1),
+        }))
+    }
+
+    /// Request an artifact over this connection's own stream instead of
+    /// opening a new HTTP request. Returns `Ok(None)` if the peer reported it
+    /// doesn't have the artifact, same as a 404 used to mean.
+    pub async fn request_artifact(&self, hash: &str, timeout: Duration) -> Result<Option<Vec<u8>>> {
+        let stream_id = self.next_stream_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(stream_id, tx);
+
+        let frame = Frame {
+            stream_id,
+            payload: FramePayload::ArtifactRequest { hash: hash.to_string() },
+        };
+        if self.outbound.send(frame).is_err() {
+            self.pending.lock().unwrap().remove(&stream_id);
+            return Err(anyhow!("peer connection is closed"));
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(sealed)) => Ok(sealed),
+            Ok(Err(_)) => Err(anyhow!("peer connection closed before responding")),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&stream_id);
+                Err(anyhow!("artifact request timed out"))
+            }
+        }
+    }
+
+    /// Push an unprompted "I just cached this" notification over this
+    /// connection.
+    pub fn announce(&self, hash: &str) -> Result<()> {
+        let frame = Frame { stream_id: 0, payload: FramePayload::Announce { hash: hash.to_string() } };
+        self.outbound.send(frame).map_err(|_| anyhow!("peer connection is closed"))
+    }
+
+    /// Send a heartbeat, with no reply waited on - just keeps the socket from
+    /// looking idle to any intermediate that times out quiet connections.
+    pub fn send_heartbeat(&self) -> Result<()> {
+        let frame = Frame { stream_id: 0, payload: FramePayload::Heartbeat };
+        self.outbound.send(frame).map_err(|_| anyhow!("peer connection is closed"))
+    }
+}
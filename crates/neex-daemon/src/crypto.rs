@@ -0,0 +1,325 @@
+//! Authenticated, Encrypted Peer Transport
+//!
+//! The P2P artifact server used to be a LAN free-for-all: bound to
+//! `0.0.0.0:0`, serving any cached blob over plaintext HTTP to whoever asked,
+//! with `fetch_from_peer` trusting whatever bytes came back. Every daemon now
+//! has a long-term X25519 [`Identity`] plus a pre-shared [`NetworkKey`] (e.g.
+//! one per team, configured out of band). `initiate_handshake` and
+//! `respond_to_handshake` prove knowledge of that key, exchange ephemeral
+//! public keys, and mix a static-static DH between the two `Identity` keys
+//! into the derived [`SessionKey`] - so a peer claiming someone else's
+//! `identity_public` without holding that identity's real secret ends up
+//! with a session key that doesn't match the legitimate side's, instead of
+//! being able to silently impersonate it. That session key seals every
+//! artifact transfer in a `ChaCha20Poly1305` box - confidential and
+//! tamper-evident - and a peer that fails the handshake never gets a session
+//! to send anything with.
+
+use anyhow::{anyhow, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+
+/// Pre-shared team/network secret. A peer that doesn't know it can't
+/// reproduce the proof in a handshake, so it never makes it into
+/// `PeerManager::peers` no matter how convincing its mDNS advertisement looks.
+#[derive(Clone)]
+pub struct NetworkKey([u8; 32]);
+
+impl NetworkKey {
+    /// Derive a network key from a team passphrase, e.g. read from config.
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        Self(*blake3::hash(passphrase.as_bytes()).as_bytes())
+    }
+}
+
+/// Derived once per handshake from both the X25519 shared secret and the
+/// network key, and used to seal every artifact transfer for the life of
+/// that session.
+#[derive(Clone)]
+pub struct SessionKey([u8; 32]);
+
+impl SessionKey {
+    /// Mixes in both the ephemeral DH (fresh per session) and a static-static
+    /// DH between the two sides' long-term `Identity` keys. The static term
+    /// is what actually binds the session to `identity_public` - a peer that
+    /// claims someone else's `identity_public` without holding that
+    /// identity's real secret computes a different static-static result than
+    /// the legitimate side does, so the two ends derive mismatched session
+    /// keys and every subsequent `encrypt`/`decrypt` fails its AEAD tag
+    /// instead of silently succeeding under a spoofed identity.
+    fn derive(network_key: &NetworkKey, ephemeral_shared: &[u8], static_shared: &[u8]) -> Self {
+        let mut data = Vec::with_capacity(ephemeral_shared.len() + static_shared.len());
+        data.extend_from_slice(ephemeral_shared);
+        data.extend_from_slice(static_shared);
+        Self(*blake3::keyed_hash(&network_key.0, &data).as_bytes())
+    }
+
+    /// Seal `plaintext` under a fresh random nonce, prefixed to the returned
+    /// bytes so the peer on the other end can split it back out.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.0));
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("failed to seal artifact"))?;
+
+        let mut out = nonce.to_vec();
+        out.append(&mut sealed);
+        Ok(out)
+    }
+
+    /// Split the nonce back out, decrypt, and verify the authentication tag.
+    pub fn decrypt(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < 12 {
+            return Err(anyhow!("ciphertext too short to contain a nonce"));
+        }
+        let (nonce_bytes, ciphertext) = sealed.split_at(12);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&self.0));
+        cipher
+            .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| anyhow!("decryption failed - tampered ciphertext or wrong session key"))
+    }
+}
+
+/// A daemon's long-term identity: an X25519 keypair plus a short fingerprint
+/// of the public key, used to name this daemon's handshake sessions the same
+/// way `PeerManager::local_id` already names it to mDNS - but now
+/// cryptographically bound instead of just a random UUID.
+pub struct Identity {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl Identity {
+    pub fn generate() -> Self {
+        let secret = StaticSecret::random_from_rng(OsRng);
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    pub fn fingerprint(&self) -> String {
+        fingerprint_of(self.public.as_bytes())
+    }
+}
+
+fn fingerprint_of(public_key: &[u8]) -> String {
+    blake3::hash(public_key).to_hex().to_string()[..16].to_string()
+}
+
+/// Sent by the side opening the connection - the daemon that just resolved a
+/// peer via mDNS and is about to decide whether to trust it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandshakeInit {
+    ephemeral_public: [u8; 32],
+    identity_public: [u8; 32],
+    /// Proof of knowledge of the network key:
+    /// `keyed_hash(network_key, ephemeral_public || identity_public)`.
+    proof: [u8; 32],
+}
+
+/// Sent back by the side that accepted the handshake.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandshakeResponse {
+    ephemeral_public: [u8; 32],
+    identity_public: [u8; 32],
+    proof: [u8; 32],
+}
+
+fn proof_of(network_key: &NetworkKey, parts: &[&[u8]]) -> [u8; 32] {
+    let mut data = Vec::new();
+    for part in parts {
+        data.extend_from_slice(part);
+    }
+    *blake3::keyed_hash(&network_key.0, &data).as_bytes()
+}
+
+/// Client side of the handshake - called once per newly-resolved mDNS peer,
+/// before it's ever inserted into `PeerManager::peers`. Any failure (network
+/// error, a proof that doesn't check out) just means the peer is never
+/// trusted; on success, returns its fingerprint and the derived session key.
+pub async fn initiate_handshake(
+    addr: std::net::SocketAddr,
+    identity: &Identity,
+    network_key: &NetworkKey,
+) -> Result<(String, SessionKey)> {
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+
+    let init = HandshakeInit {
+        ephemeral_public: *ephemeral_public.as_bytes(),
+        identity_public: *identity.public.as_bytes(),
+        proof: proof_of(
+            network_key,
+            &[ephemeral_public.as_bytes(), identity.public.as_bytes()],
+        ),
+    };
+
+    let url = format!("http://{}/handshake", addr);
+    let resp = reqwest::Client::new().post(&url).json(&init).send().await?;
+
+    if !resp.status().is_success() {
+        return Err(anyhow!("peer {} rejected the handshake", addr));
+    }
+
+    let response: HandshakeResponse = resp.json().await?;
+    let their_ephemeral = PublicKey::from(response.ephemeral_public);
+
+    let expected_proof = proof_of(
+        network_key,
+        &[
+            &response.ephemeral_public,
+            &response.identity_public,
+            ephemeral_public.as_bytes(),
+        ],
+    );
+    if expected_proof != response.proof {
+        return Err(anyhow!("peer {} failed to prove the network key", addr));
+    }
+
+    let shared_secret = ephemeral.diffie_hellman(&their_ephemeral);
+    let their_identity = PublicKey::from(response.identity_public);
+    let static_shared = identity.secret.diffie_hellman(&their_identity);
+    let session_key = SessionKey::derive(network_key, shared_secret.as_bytes(), static_shared.as_bytes());
+
+    Ok((fingerprint_of(&response.identity_public), session_key))
+}
+
+/// Server side of the handshake - called from the `/handshake` route. A bad
+/// proof is rejected before an ephemeral key is even generated, so there's
+/// never a session to derive from a peer that doesn't know the network key.
+pub fn respond_to_handshake(
+    init: &HandshakeInit,
+    identity: &Identity,
+    network_key: &NetworkKey,
+) -> Result<(String, SessionKey, HandshakeResponse)> {
+    let expected_proof = proof_of(network_key, &[&init.ephemeral_public, &init.identity_public]);
+    if expected_proof != init.proof {
+        return Err(anyhow!("initiator failed to prove the network key"));
+    }
+
+    let their_ephemeral = PublicKey::from(init.ephemeral_public);
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+
+    let response = HandshakeResponse {
+        ephemeral_public: *ephemeral_public.as_bytes(),
+        identity_public: *identity.public.as_bytes(),
+        proof: proof_of(
+            network_key,
+            &[
+                ephemeral_public.as_bytes(),
+                identity.public.as_bytes(),
+                &init.ephemeral_public,
+            ],
+        ),
+    };
+
+    let shared_secret = ephemeral.diffie_hellman(&their_ephemeral);
+    let their_identity = PublicKey::from(init.identity_public);
+    let static_shared = identity.secret.diffie_hellman(&their_identity);
+    let session_key = SessionKey::derive(network_key, shared_secret.as_bytes(), static_shared.as_bytes());
+
+    Ok((fingerprint_of(&init.identity_public), session_key, response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_derives_matching_session_keys() {
+        let network_key = NetworkKey::from_passphrase("team-secret");
+        let initiator = Identity::generate();
+        let responder = Identity::generate();
+
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let init = HandshakeInit {
+            ephemeral_public: *ephemeral_public.as_bytes(),
+            identity_public: *initiator.public.as_bytes(),
+            proof: proof_of(
+                &network_key,
+                &[ephemeral_public.as_bytes(), initiator.public.as_bytes()],
+            ),
+        };
+
+        let (_, server_session, response) =
+            respond_to_handshake(&init, &responder, &network_key).unwrap();
+
+        let their_ephemeral = PublicKey::from(response.ephemeral_public);
+        let shared_secret = ephemeral.diffie_hellman(&their_ephemeral);
+        let their_identity = PublicKey::from(response.identity_public);
+        let static_shared = initiator.secret.diffie_hellman(&their_identity);
+        let client_session =
+            SessionKey::derive(&network_key, shared_secret.as_bytes(), static_shared.as_bytes());
+
+        let sealed = server_session.encrypt(b"hello from the responder").unwrap();
+        let opened = client_session.decrypt(&sealed).unwrap();
+        assert_eq!(opened, b"hello from the responder");
+    }
+
+    #[test]
+    fn test_handshake_rejects_spoofed_identity() {
+        // An attacker who knows the network key (anyone on the team does) but
+        // not the real initiator's static secret tries to open a session
+        // while *claiming* to be that initiator.
+        let network_key = NetworkKey::from_passphrase("team-secret");
+        let real_initiator = Identity::generate();
+        let attacker = Identity::generate();
+        let responder = Identity::generate();
+
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let init = HandshakeInit {
+            ephemeral_public: *ephemeral_public.as_bytes(),
+            // Claims the real initiator's identity...
+            identity_public: *real_initiator.public.as_bytes(),
+            // ...but the proof only needs the network key, which the
+            // attacker also knows, so it checks out fine.
+            proof: proof_of(
+                &network_key,
+                &[ephemeral_public.as_bytes(), real_initiator.public.as_bytes()],
+            ),
+        };
+
+        let (_, server_session, response) =
+            respond_to_handshake(&init, &responder, &network_key).unwrap();
+
+        // The attacker completes the ephemeral DH normally, but can only mix
+        // in a static-static DH using its own secret, not the real
+        // initiator's.
+        let their_ephemeral = PublicKey::from(response.ephemeral_public);
+        let shared_secret = ephemeral.diffie_hellman(&their_ephemeral);
+        let their_identity = PublicKey::from(response.identity_public);
+        let static_shared = attacker.secret.diffie_hellman(&their_identity);
+        let attacker_session =
+            SessionKey::derive(&network_key, shared_secret.as_bytes(), static_shared.as_bytes());
+
+        let sealed = server_session.encrypt(b"secret artifact bytes").unwrap();
+        assert!(attacker_session.decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn test_handshake_rejects_wrong_network_key() {
+        let network_key = NetworkKey::from_passphrase("team-secret");
+        let wrong_key = NetworkKey::from_passphrase("not-the-team-secret");
+        let initiator = Identity::generate();
+        let responder = Identity::generate();
+
+        let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let init = HandshakeInit {
+            ephemeral_public: *ephemeral_public.as_bytes(),
+            identity_public: *initiator.public.as_bytes(),
+            proof: proof_of(
+                &wrong_key,
+                &[ephemeral_public.as_bytes(), initiator.public.as_bytes()],
+            ),
+        };
+
+        assert!(respond_to_handshake(&init, &responder, &network_key).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Resumable Job Store
+//!
+//! Sled-backed persistence for `neex_core::JobState` records, kept in a
+//! dedicated tree so they never collide with the file-hash keys `DaemonState`
+//! stores in the default tree. See `neex_core::resumable` for the actual
+//! resume-decision logic - this module is only the read/write side.
+
+use anyhow::Result;
+use neex_core::JobState;
+use sled::Tree;
+
+const JOBS_TREE: &str = "jobs";
+
+#[derive(Clone)]
+pub struct JobStore {
+    tree: Tree,
+}
+
+impl JobStore {
+    pub fn new(db: &sled::Db) -> Result<Self> {
+        Ok(Self {
+            tree: db.open_tree(JOBS_TREE)?,
+        })
+    }
+
+    /// Persist (or overwrite) `job`'s record. Call this after every task
+    /// status transition and whenever a shutdown signal fires, so the
+    /// on-disk state never lags more than one transition behind reality.
+    pub fn save(&self, job: &JobState) -> Result<()> {
+        self.tree.insert(job.task_name.as_bytes(), job.encode()?)?;
+        Ok(())
+    }
+
+    /// Drop a job's record once it no longer needs to be resumed (its
+    /// output was replayed, or the resume plan discarded it).
+    pub fn remove(&self, task_name: &str) -> Result<()> {
+        self.tree.remove(task_name.as_bytes())?;
+        Ok(())
+    }
+
+    /// Every persisted job record, in no particular order.
+    pub fn load_all(&self) -> Result<Vec<JobState>> {
+        let mut jobs = Vec::new();
+        for item in self.tree.iter() {
+            let (_, value) = item?;
+            jobs.push(JobState::decode(&value)?);
+        }
+        Ok(jobs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> sled::Db {
+        sled::Config::new().temporary(true).open().unwrap()
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let db = temp_db();
+        let store = JobStore::new(&db).unwrap();
+
+        let job = JobState::new("web:build", "abc123");
+        store.save(&job).unwrap();
+
+        let loaded = store.load_all().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0], job);
+    }
+
+    #[test]
+    fn test_remove_drops_the_record() {
+        let db = temp_db();
+        let store = JobStore::new(&db).unwrap();
+
+        store.save(&JobState::new("web:build", "abc123")).unwrap();
+        store.remove("web:build").unwrap();
+
+        assert!(store.load_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_overwrites_existing_record() {
+        let db = temp_db();
+        let store = JobStore::new(&db).unwrap();
+
+        let mut job = JobState::new("web:build", "abc123");
+        store.save(&job).unwrap();
+
+        job.step_cursor = 3;
+        store.save(&job).unwrap();
+
+        let loaded = store.load_all().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].step_cursor, 3);
+    }
+}
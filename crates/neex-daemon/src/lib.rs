@@ -10,12 +10,22 @@
 //! - P2P LAN cache sharing via mDNS
 //! - Zero startup time for builds
 
+pub mod bloom;
+mod conn;
+pub mod crypto;
+pub mod jobs;
+pub mod living_graph;
 pub mod p2p;
 pub mod server;
 pub mod state;
 pub mod watcher;
 
-pub use p2p::{start_artifact_server, PeerManager};
+pub use bloom::BloomFilter;
+pub use crypto::{Identity, NetworkKey, SessionKey};
+pub use jobs::JobStore;
+pub use living_graph::LivingGraph;
+pub use neex_core::worker::{Worker, WorkerInfo, WorkerRegistry, WorkerState};
+pub use p2p::{start_artifact_server, DiscoveryMode, PeerManager};
 pub use server::{DaemonRequest, DaemonResponse, DaemonServer};
 pub use state::DaemonState;
 pub use watcher::FileWatcher;
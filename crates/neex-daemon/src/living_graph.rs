@@ -0,0 +1,209 @@
+//! Living Dependency Graph
+//!
+//! `neex_core::DepGraph` is otherwise only ever built once via `from_root`.
+//! [`LivingGraph`] wraps one behind a lock and keeps it current as
+//! `package.json` files change: a changed manifest is folded in with
+//! `DepGraph::upsert_package`/`remove_package` instead of rebuilding the
+//! whole graph from scratch.
+//!
+//! Queries like `get_affected`/`get_build_order` can otherwise race
+//! in-flight filesystem events - a change might already be on disk but not
+//! yet folded into the graph. [`LivingGraph::request_cookie`] writes a
+//! uniquely-numbered sentinel file into the watched root and blocks until
+//! `apply_changes` reports having seen that exact sentinel (or a later
+//! one) - since a single watched directory's events are delivered in
+//! order, observing a later cookie guarantees every earlier change is
+//! already reflected in the graph.
+
+use anyhow::{anyhow, Result};
+use neex_core::DepGraph;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::watcher::{ChangeKind, FileChange};
+
+const COOKIE_PREFIX: &str = ".neex-cookie-";
+
+/// Default timeout for `request_cookie` - generous enough to absorb a
+/// `watch_loop` debounce window, small enough that a caller isn't left
+/// hanging if the watcher isn't running at all.
+pub const DEFAULT_COOKIE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A [`DepGraph`] kept current by repeated `apply_changes` calls, plus the
+/// serial/waiter bookkeeping `request_cookie` needs to order queries
+/// against in-flight filesystem events.
+pub struct LivingGraph {
+    graph: Arc<RwLock<DepGraph>>,
+    root: PathBuf,
+    next_serial: AtomicU64,
+    /// Waiters for a not-yet-observed cookie, keyed by serial so one
+    /// observed sentinel can release every earlier-or-equal pending waiter
+    /// in a single pass.
+    pending: Mutex<BTreeMap<u64, Sender<()>>>,
+}
+
+impl LivingGraph {
+    pub fn new(graph: DepGraph, root: impl Into<PathBuf>) -> Self {
+        Self {
+            graph: Arc::new(RwLock::new(graph)),
+            root: root.into(),
+            next_serial: AtomicU64::new(0),
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Shared handle to the live graph, for `get_affected`/`get_build_order`
+    /// and friends to read from directly.
+    pub fn graph(&self) -> Arc<RwLock<DepGraph>> {
+        Arc::clone(&self.graph)
+    }
+
+    /// Fold one batch of changes (as produced by `FileWatcher::watch_loop`)
+    /// into the graph: a created/modified `package.json` is re-parsed and
+    /// upserted, a deleted one drops its package, and a cookie sentinel
+    /// releases whatever queries were waiting on it or an earlier one.
+    pub fn apply_changes(&self, changes: &[FileChange]) {
+        for change in changes {
+            if let Some(serial) = cookie_serial(&change.path) {
+                self.release_through(serial);
+                continue;
+            }
+
+            if change.path.file_name().and_then(|n| n.to_str()) != Some("package.json") {
+                continue;
+            }
+
+            let Some(ws_path) = change.path.parent() else {
+                continue;
+            };
+            let mut graph = self.graph.write().unwrap();
+
+            match change.kind {
+                ChangeKind::Delete => {
+                    if let Some(name) = graph.package_name_at(ws_path) {
+                        graph.remove_package(&name);
+                    }
+                }
+                ChangeKind::Create | ChangeKind::Modify => {
+                    if let Err(e) = graph.upsert_package(ws_path) {
+                        warn!("Failed to update workspace at {:?}: {}", ws_path, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write a uniquely-numbered sentinel file into the watched root and
+    /// block (up to `timeout`) until `apply_changes` reports having seen
+    /// it, guaranteeing every filesystem change issued before this call
+    /// returns is already folded into the graph.
+    pub fn request_cookie(&self, timeout: Duration) -> Result<()> {
+        let serial = self.next_serial.fetch_add(1, Ordering::SeqCst);
+        let cookie_path = self.root.join(format!("{COOKIE_PREFIX}{serial}"));
+
+        let (tx, rx) = channel();
+        self.pending.lock().unwrap().insert(serial, tx);
+
+        let result = std::fs::write(&cookie_path, b"").map_err(anyhow::Error::from).and_then(|_| {
+            rx.recv_timeout(timeout)
+                .map_err(|_| anyhow!("cookie {serial} timed out after {timeout:?} waiting for the watcher to observe it"))
+        });
+
+        let _ = std::fs::remove_file(&cookie_path);
+        self.pending.lock().unwrap().remove(&serial);
+
+        result
+    }
+
+    /// `request_cookie` with `DEFAULT_COOKIE_TIMEOUT`.
+    pub fn request_cookie_default(&self) -> Result<()> {
+        self.request_cookie(DEFAULT_COOKIE_TIMEOUT)
+    }
+
+    /// Release every pending waiter with a serial at or before `serial` -
+    /// a single watched directory's filesystem events are delivered in
+    /// order, so observing cookie N means every earlier cookie's sentinel
+    /// (and therefore every change issued before it) was already observed.
+    fn release_through(&self, serial: u64) {
+        let mut pending = self.pending.lock().unwrap();
+        let ready: Vec<u64> = pending.range(..=serial).map(|(&s, _)| s).collect();
+        for s in ready {
+            if let Some(tx) = pending.remove(&s) {
+                let _ = tx.send(());
+            }
+        }
+    }
+}
+
+fn cookie_serial(path: &Path) -> Option<u64> {
+    path.file_name()?.to_str()?.strip_prefix(COOKIE_PREFIX)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neex_core::DepGraph;
+
+    #[test]
+    fn test_cookie_serial_parses_sentinel_names_only() {
+        assert_eq!(cookie_serial(Path::new(".neex-cookie-42")), Some(42));
+        assert_eq!(cookie_serial(Path::new("package.json")), None);
+    }
+
+    #[test]
+    fn test_apply_changes_releases_matching_and_earlier_cookies() {
+        let living = LivingGraph::new(DepGraph::new(), PathBuf::from("/tmp"));
+        let (tx1, rx1) = std::sync::mpsc::channel();
+        let (tx2, rx2) = std::sync::mpsc::channel();
+        living.pending.lock().unwrap().insert(1, tx1);
+        living.pending.lock().unwrap().insert(2, tx2);
+
+        let sentinel = FileChange {
+            path: living.root.join(format!("{COOKIE_PREFIX}2")),
+            kind: ChangeKind::Create,
+            package_root: None,
+        };
+        living.apply_changes(&[sentinel]);
+
+        assert!(rx1.try_recv().is_ok(), "an earlier cookie should resolve alongside a later one");
+        assert!(rx2.try_recv().is_ok());
+        assert!(living.pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_request_cookie_times_out_without_a_watcher() {
+        let living = LivingGraph::new(DepGraph::new(), std::env::temp_dir());
+        assert!(living.request_cookie(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn test_apply_changes_upserts_and_removes_packages() {
+        let dir = std::env::temp_dir().join(format!("neex-living-graph-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("pkg")).unwrap();
+        std::fs::write(dir.join("pkg/package.json"), r#"{"name": "@my/pkg", "version": "1.0.0"}"#)
+            .unwrap();
+
+        let living = LivingGraph::new(DepGraph::new(), &dir);
+        living.apply_changes(&[FileChange {
+            path: dir.join("pkg/package.json"),
+            kind: ChangeKind::Create,
+            package_root: None,
+        }]);
+        assert!(living.graph().read().unwrap().get_package("@my/pkg").is_some());
+
+        std::fs::remove_file(dir.join("pkg/package.json")).unwrap();
+        living.apply_changes(&[FileChange {
+            path: dir.join("pkg/package.json"),
+            kind: ChangeKind::Delete,
+            package_root: None,
+        }]);
+        assert!(living.graph().read().unwrap().get_package("@my/pkg").is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -4,27 +4,90 @@
 //! Zero config, automatic discovery via mDNS.
 //!
 //! Architecture:
-//! - Discovery: mDNS broadcasts "_neex._tcp" service
-//! - Server: HTTP artifact server on random port
-//! - Client: Fetches from discovered peers
+//! - Discovery: mDNS broadcasts "_neex._tcp" service by default, or a
+//!   statically configured peer list via `DiscoveryMode::Static` for
+//!   networks where multicast is blocked
+//! - Server: HTTP artifact server on random port, plus a `/ws` upgrade for
+//!   the long-lived multiplexed connection (see `crate::conn`)
+//! - Client: Fetches from discovered peers over one `PeerConnection` per
+//!   peer instead of a fresh HTTP request each time
 
 use anyhow::Result;
 use axum::{
+    extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade},
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::get,
-    Router,
+    routing::{get, post},
+    Json, Router,
 };
 use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
+use crate::bloom::BloomFilter;
+use crate::conn::{Frame, FramePayload, PeerConnection};
+use crate::crypto::{
+    initiate_handshake, respond_to_handshake, HandshakeInit, Identity, NetworkKey, SessionKey,
+};
+
 const SERVICE_TYPE: &str = "_neex._tcp.local.";
 const SERVICE_NAME_PREFIX: &str = "neex-daemon";
+/// Header a peer sends its own identity fingerprint on, so the artifact
+/// server can find the session key established for it during the handshake.
+pub(crate) const PEER_HEADER: &str = "x-neex-peer";
+/// Number of hash-mismatch strikes a peer can accrue before it's dropped
+/// from the `peers` map entirely.
+const MAX_STRIKES: u32 = 3;
+/// How often statically configured peers are re-probed to drop dead ones.
+const STATIC_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+/// Max number of peers raced concurrently in `fetch_from_network`.
+const FETCH_CONCURRENCY: usize = 4;
+/// Per-peer timeout for a single fetch attempt in the race, so one hung peer
+/// can't hold up the others.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often each peer's cache-availability digest is re-fetched.
+const DIGEST_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Target false positive rate for `/digest` Bloom filters - tunable
+/// bandwidth/precision tradeoff.
+pub(crate) const DIGEST_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Rolling fetch performance for a peer, used to race toward the
+/// historically fastest-responding peers first.
+#[derive(Debug, Clone, Copy)]
+struct PeerStats {
+    avg_latency_ms: f64,
+    samples: u32,
+}
+
+impl Default for PeerStats {
+    fn default() -> Self {
+        Self { avg_latency_ms: 0.0, samples: 0 }
+    }
+}
+
+/// Whether a fetched, decrypted blob actually hashes to what was requested -
+/// kept distinct from a plain fetch error so callers can tell a hash
+/// mismatch (worth a strike) apart from an ordinary network failure.
+enum FetchOutcome {
+    Verified(Vec<u8>),
+    HashMismatch,
+}
+
+/// How peers are found. mDNS is the zero-config default; `Static` is for
+/// locked-down networks where multicast is blocked or disallowed.
+#[derive(Debug, Clone)]
+pub enum DiscoveryMode {
+    /// Advertise and browse via mDNS, as `PeerManager::start` always did.
+    Mdns,
+    /// Skip mDNS entirely and only ever trust these explicit addresses.
+    Static(Vec<SocketAddr>),
+}
 
 /// Peer info discovered via mDNS
 #[derive(Debug, Clone)]
@@ -37,15 +100,44 @@ pub struct PeerInfo {
 /// Manages discovered peers
 pub struct PeerManager {
     peers: Arc<RwLock<HashMap<String, PeerInfo>>>,
+    /// Session key for each trusted peer, keyed by `PeerInfo::id` - only
+    /// populated once that peer has passed the handshake in `start`.
+    sessions: Arc<RwLock<HashMap<String, SessionKey>>>,
+    /// Hash-mismatch strikes per peer, keyed by `PeerInfo::id` - a peer that
+    /// reaches `MAX_STRIKES` is dropped from `peers` in `record_strike`.
+    strikes: Arc<RwLock<HashMap<String, u32>>>,
+    /// Rolling average fetch latency per peer, keyed by `PeerInfo::id` -
+    /// consulted by `fetch_from_network` to race the fastest peers first.
+    stats: Arc<RwLock<HashMap<String, PeerStats>>>,
+    /// Cached cache-availability digest per peer, keyed by `PeerInfo::id` -
+    /// refreshed on connect and on a timer, consulted by
+    /// `fetch_from_network` to skip peers that probably don't have a hash.
+    digests: Arc<RwLock<HashMap<String, BloomFilter>>>,
+    /// One multiplexed connection per authenticated peer, keyed by
+    /// `PeerInfo::id` - opened once right after the handshake instead of
+    /// reconnecting per artifact fetch.
+    connections: Arc<RwLock<HashMap<String, Arc<PeerConnection>>>>,
     local_id: String,
+    identity: Arc<Identity>,
+    network_key: NetworkKey,
     mdns: Option<ServiceDaemon>,
 }
 
 impl PeerManager {
-    pub fn new() -> Self {
+    /// `network_key` is the team's pre-shared secret (e.g.
+    /// `NetworkKey::from_passphrase`) - only peers that can prove they know
+    /// it during the handshake in `start` are ever trusted.
+    pub fn new(network_key: NetworkKey) -> Self {
         Self {
             peers: Arc::new(RwLock::new(HashMap::new())),
+            sessions: Arc::new(RwLock::new(HashMap::new())),
+            strikes: Arc::new(RwLock::new(HashMap::new())),
+            stats: Arc::new(RwLock::new(HashMap::new())),
+            digests: Arc::new(RwLock::new(HashMap::new())),
+            connections: Arc::new(RwLock::new(HashMap::new())),
             local_id: Uuid::new_v4().to_string()[..8].to_string(),
+            identity: Arc::new(Identity::generate()),
+            network_key,
             mdns: None,
         }
     }
@@ -55,8 +147,41 @@ impl PeerManager {
         &self.local_id
     }
 
-    /// Start mDNS discovery and advertisement
+    /// This daemon's long-term identity, shared with `start_artifact_server`
+    /// so both sides of a handshake (client here, server there) answer for
+    /// the same keypair.
+    pub fn identity(&self) -> Arc<Identity> {
+        Arc::clone(&self.identity)
+    }
+
+    /// This daemon's pre-shared network key, shared with
+    /// `start_artifact_server` for the same reason as `identity`.
+    pub fn network_key(&self) -> NetworkKey {
+        self.network_key.clone()
+    }
+
+    /// Start mDNS discovery and advertisement - the zero-config default.
     pub async fn start(&mut self, local_port: u16) -> Result<()> {
+        self.start_with_config(local_port, DiscoveryMode::Mdns).await
+    }
+
+    /// Start peer discovery under an explicit `DiscoveryMode`. Whether peers
+    /// arrive via mDNS or a static address list, they land in the same
+    /// `peers`/`sessions` maps only after passing the handshake, so
+    /// `fetch_from_network` works identically either way.
+    pub async fn start_with_config(&mut self, local_port: u16, mode: DiscoveryMode) -> Result<()> {
+        self.spawn_digest_refresher();
+
+        let static_addrs = match mode {
+            DiscoveryMode::Mdns => None,
+            DiscoveryMode::Static(addrs) => Some(addrs),
+        };
+
+        if let Some(addrs) = static_addrs {
+            self.start_static(addrs);
+            return Ok(());
+        }
+
         let mdns = ServiceDaemon::new()?;
 
         // Advertise our service
@@ -76,7 +201,14 @@ impl PeerManager {
         // Browse for peers
         let receiver = mdns.browse(SERVICE_TYPE)?;
         let peers = Arc::clone(&self.peers);
+        let sessions = Arc::clone(&self.sessions);
+        let strikes = Arc::clone(&self.strikes);
+        let stats = Arc::clone(&self.stats);
+        let digests = Arc::clone(&self.digests);
+        let connections = Arc::clone(&self.connections);
         let local_id = self.local_id.clone();
+        let identity = Arc::clone(&self.identity);
+        let network_key = self.network_key.clone();
 
         tokio::spawn(async move {
             while let Ok(event) = receiver.recv() {
@@ -88,18 +220,66 @@ impl PeerManager {
                         }
 
                         for addr in info.get_addresses() {
-                            let peer = PeerInfo {
-                                id: info.get_fullname().to_string(),
-                                addr: SocketAddr::new(*addr, info.get_port()),
-                                hostname: info.get_hostname().to_string(),
-                            };
-
-                            tracing::info!("🔗 Peer found: {} at {}", peer.hostname, peer.addr);
-                            peers.write().await.insert(peer.id.clone(), peer);
+                            let peer_addr = SocketAddr::new(*addr, info.get_port());
+
+                            // Only trust a peer once it's proven it knows our
+                            // network key - a failed handshake just means
+                            // this address is never inserted into `peers`.
+                            match initiate_handshake(peer_addr, &identity, &network_key).await {
+                                Ok((peer_fingerprint, session_key)) => {
+                                    let peer = PeerInfo {
+                                        id: info.get_fullname().to_string(),
+                                        addr: peer_addr,
+                                        hostname: info.get_hostname().to_string(),
+                                    };
+
+                                    tracing::info!(
+                                        "🔗 Peer authenticated: {} at {} ({})",
+                                        peer.hostname,
+                                        peer.addr,
+                                        peer_fingerprint
+                                    );
+                                    sessions.write().await.insert(peer.id.clone(), session_key);
+                                    if let Ok(digest) = fetch_digest(peer_addr).await {
+                                        digests.write().await.insert(peer.id.clone(), digest);
+                                    }
+                                    match PeerConnection::connect(
+                                        peer_addr,
+                                        identity.fingerprint(),
+                                        peer.id.clone(),
+                                        Arc::clone(&digests),
+                                    )
+                                    .await
+                                    {
+                                        Ok(conn) => {
+                                            connections.write().await.insert(peer.id.clone(), conn);
+                                        }
+                                        Err(e) => tracing::warn!(
+                                            "Failed to open multiplexed connection to {}: {}",
+                                            peer.addr,
+                                            e
+                                        ),
+                                    }
+                                    peers.write().await.insert(peer.id.clone(), peer);
+                                }
+                                Err(e) => {
+                                    tracing::warn!(
+                                        "🚫 Peer {} at {} failed handshake, not trusting it: {}",
+                                        info.get_hostname(),
+                                        peer_addr,
+                                        e
+                                    );
+                                }
+                            }
                         }
                     }
                     ServiceEvent::ServiceRemoved(_, name) => {
                         peers.write().await.remove(&name);
+                        sessions.write().await.remove(&name);
+                        strikes.write().await.remove(&name);
+                        stats.write().await.remove(&name);
+                        digests.write().await.remove(&name);
+                        connections.write().await.remove(&name);
                         tracing::info!("🔌 Peer left: {}", name);
                     }
                     _ => {}
@@ -111,61 +291,404 @@ impl PeerManager {
         Ok(())
     }
 
+    /// Spawn the background loop for `DiscoveryMode::Static`: probe each
+    /// configured address's `/health` endpoint, handshake with anything
+    /// that answers, and drop addresses that stop answering.
+    fn start_static(&mut self, addrs: Vec<SocketAddr>) {
+        let peers = Arc::clone(&self.peers);
+        let sessions = Arc::clone(&self.sessions);
+        let strikes = Arc::clone(&self.strikes);
+        let stats = Arc::clone(&self.stats);
+        let digests = Arc::clone(&self.digests);
+        let connections = Arc::clone(&self.connections);
+        let identity = Arc::clone(&self.identity);
+        let network_key = self.network_key.clone();
+
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut interval = tokio::time::interval(STATIC_PROBE_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                for &addr in &addrs {
+                    let id = addr.to_string();
+                    let healthy = client
+                        .get(format!("http://{}/health", addr))
+                        .send()
+                        .await
+                        .map(|resp| resp.status().is_success())
+                        .unwrap_or(false);
+
+                    if !healthy {
+                        if peers.write().await.remove(&id).is_some() {
+                            sessions.write().await.remove(&id);
+                            strikes.write().await.remove(&id);
+                            stats.write().await.remove(&id);
+                            digests.write().await.remove(&id);
+                            connections.write().await.remove(&id);
+                            tracing::info!("🔌 Static peer {} stopped responding, dropping it", addr);
+                        }
+                        continue;
+                    }
+
+                    if sessions.read().await.contains_key(&id) {
+                        continue;
+                    }
+
+                    match initiate_handshake(addr, &identity, &network_key).await {
+                        Ok((peer_fingerprint, session_key)) => {
+                            let peer = PeerInfo {
+                                id: id.clone(),
+                                addr,
+                                hostname: addr.to_string(),
+                            };
+                            tracing::info!(
+                                "🔗 Static peer authenticated: {} ({})",
+                                addr, peer_fingerprint
+                            );
+                            sessions.write().await.insert(id.clone(), session_key);
+                            if let Ok(digest) = fetch_digest(addr).await {
+                                digests.write().await.insert(id.clone(), digest);
+                            }
+                            match PeerConnection::connect(
+                                addr,
+                                identity.fingerprint(),
+                                id.clone(),
+                                Arc::clone(&digests),
+                            )
+                            .await
+                            {
+                                Ok(conn) => {
+                                    connections.write().await.insert(id.clone(), conn);
+                                }
+                                Err(e) => tracing::warn!(
+                                    "Failed to open multiplexed connection to static peer {}: {}",
+                                    addr,
+                                    e
+                                ),
+                            }
+                            peers.write().await.insert(id, peer);
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                "🚫 Static peer {} failed handshake, not trusting it: {}",
+                                addr, e
+                            );
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Spawn the background loop that periodically re-fetches every
+    /// currently known peer's cache-availability digest and sends a
+    /// heartbeat over its multiplexed connection, independent of which
+    /// `DiscoveryMode` found them.
+    fn spawn_digest_refresher(&self) {
+        let peers = Arc::clone(&self.peers);
+        let digests = Arc::clone(&self.digests);
+        let connections = Arc::clone(&self.connections);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DIGEST_REFRESH_INTERVAL);
+            loop {
+                interval.tick().await;
+
+                let snapshot: Vec<PeerInfo> = peers.read().await.values().cloned().collect();
+                for peer in snapshot {
+                    if let Ok(digest) = fetch_digest(peer.addr).await {
+                        digests.write().await.insert(peer.id.clone(), digest);
+                    }
+                    if let Some(conn) = connections.read().await.get(&peer.id) {
+                        let _ = conn.send_heartbeat();
+                    }
+                }
+            }
+        });
+    }
+
     /// Get list of active peers
     pub async fn get_peers(&self) -> Vec<PeerInfo> {
         self.peers.read().await.values().cloned().collect()
     }
 
-    /// Fetch artifact from a peer
+    /// Fetch artifact from a peer, over the session established when it
+    /// passed the handshake in `start`. Decrypts and authenticates the
+    /// response with that session key, so tampered or wrongly-keyed bytes
+    /// are rejected instead of silently trusted - then, since the request is
+    /// content-addressed, recomputes the artifact's own hash and rejects it
+    /// (striking the peer) if it doesn't match what was asked for.
     pub async fn fetch_from_peer(&self, peer: &PeerInfo, hash: &str) -> Result<Vec<u8>> {
-        let url = format!("http://{}/artifact/{}", peer.addr, hash);
-        let resp = reqwest::get(&url).await?;
+        let session_key = self
+            .sessions
+            .read()
+            .await
+            .get(&peer.id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no authenticated session with peer {}", peer.addr))?;
+        let connection = self.connections.read().await.get(&peer.id).cloned();
+
+        match fetch_and_verify(peer, hash, &session_key, &self.identity.fingerprint(), connection)
+            .await?
+        {
+            FetchOutcome::Verified(data) => Ok(data),
+            FetchOutcome::HashMismatch => {
+                self.record_strike(&peer.id).await;
+                Err(anyhow::anyhow!(
+                    "Peer {} returned data that doesn't match requested hash {}",
+                    peer.addr,
+                    hash
+                ))
+            }
+        }
+    }
 
-        if resp.status().is_success() {
-            Ok(resp.bytes().await?.to_vec())
-        } else {
-            Err(anyhow::anyhow!("Peer {} doesn't have artifact", peer.addr))
+    /// Push "I just cached this" to every peer with an open multiplexed
+    /// connection, so they can update their digest of us without waiting for
+    /// the next scheduled `/digest` refresh.
+    pub async fn announce_to_all(&self, hash: &str) {
+        for conn in self.connections.read().await.values() {
+            let _ = conn.announce(hash);
+        }
+    }
+
+    /// Record a hash-mismatch strike against a peer, dropping it from
+    /// `peers` once it reaches `MAX_STRIKES` so a misconfigured or malicious
+    /// peer can't keep poisoning the cache with wrong outputs.
+    async fn record_strike(&self, peer_id: &str) {
+        let count = {
+            let mut strikes = self.strikes.write().await;
+            let count = strikes.entry(peer_id.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+
+        tracing::warn!(
+            "⚠️ Peer {} returned a bad artifact ({}/{} strikes)",
+            peer_id,
+            count,
+            MAX_STRIKES
+        );
+
+        if count >= MAX_STRIKES {
+            self.peers.write().await.remove(peer_id);
+            self.sessions.write().await.remove(peer_id);
+            self.connections.write().await.remove(peer_id);
+            tracing::warn!("🚫 Peer {} exceeded strike limit, dropping it", peer_id);
         }
     }
 
-    /// Try to fetch artifact from any peer
+    /// Fold one fetch attempt's latency into a peer's rolling average, so
+    /// future rounds in `fetch_from_network` race toward it earlier (or
+    /// later, if it keeps timing out or failing).
+    async fn record_latency(&self, peer_id: &str, elapsed: Duration, success: bool) {
+        let sample_ms = if success {
+            elapsed.as_secs_f64() * 1000.0
+        } else {
+            FETCH_TIMEOUT.as_secs_f64() * 1000.0
+        };
+
+        let mut stats = self.stats.write().await;
+        let entry = stats.entry(peer_id.to_string()).or_default();
+        entry.samples += 1;
+        entry.avg_latency_ms += (sample_ms - entry.avg_latency_ms) / entry.samples as f64;
+    }
+
+    /// Race a bounded number of peers at once for an artifact, returning as
+    /// soon as one comes back verified and cancelling the rest. Peers are
+    /// tried in order of historically lowest average latency first, so a
+    /// consistently slow or unresponsive peer stops being raced early, and
+    /// any peer whose cached digest says it definitely doesn't have `hash`
+    /// is skipped entirely - falling back to asking everyone if no digest
+    /// has been fetched for a peer yet.
     pub async fn fetch_from_network(&self, hash: &str) -> Option<Vec<u8>> {
-        let peers = self.get_peers().await;
+        let mut peers = self.get_peers().await;
+        if peers.is_empty() {
+            return None;
+        }
+
+        let digests = self.digests.read().await.clone();
+        peers.retain(|p| digests.get(&p.id).is_none_or(|d| d.might_contain(hash)));
+        if peers.is_empty() {
+            return None;
+        }
 
-        for peer in peers {
-            match self.fetch_from_peer(&peer, hash).await {
-                Ok(data) => {
-                    tracing::info!("📥 Got artifact from peer: {}", peer.hostname);
-                    return Some(data);
+        let stats_snapshot = self.stats.read().await.clone();
+        peers.sort_by(|a, b| {
+            let a_ms = stats_snapshot.get(&a.id).map(|s| s.avg_latency_ms).unwrap_or(f64::MAX);
+            let b_ms = stats_snapshot.get(&b.id).map(|s| s.avg_latency_ms).unwrap_or(f64::MAX);
+            a_ms.total_cmp(&b_ms)
+        });
+
+        let sessions = self.sessions.read().await.clone();
+        let connections = self.connections.read().await.clone();
+        let my_fingerprint = self.identity.fingerprint();
+        let hash = hash.to_string();
+
+        let mut queue: VecDeque<PeerInfo> = peers
+            .into_iter()
+            .filter(|p| sessions.contains_key(&p.id))
+            .collect();
+
+        let mut in_flight: JoinSet<(PeerInfo, Duration, Result<FetchOutcome>)> = JoinSet::new();
+        for _ in 0..FETCH_CONCURRENCY {
+            let Some(peer) = queue.pop_front() else { break };
+            let session_key = sessions[&peer.id].clone();
+            let connection = connections.get(&peer.id).cloned();
+            in_flight.spawn(race_peer(peer, hash.clone(), session_key, my_fingerprint.clone(), connection));
+        }
+
+        let mut winner = None;
+        while let Some(joined) = in_flight.join_next().await {
+            let Ok((peer, elapsed, outcome)) = joined else { continue };
+
+            match outcome {
+                Ok(FetchOutcome::Verified(data)) => {
+                    self.record_latency(&peer.id, elapsed, true).await;
+                    tracing::info!("📥 Got artifact from peer: {} ({:?})", peer.hostname, elapsed);
+                    winner = Some(data);
+                    break;
+                }
+                Ok(FetchOutcome::HashMismatch) => {
+                    self.record_strike(&peer.id).await;
+                    self.record_latency(&peer.id, elapsed, false).await;
+                }
+                Err(e) => {
+                    tracing::debug!("Peer {} failed this round: {}", peer.hostname, e);
+                    self.record_latency(&peer.id, elapsed, false).await;
                 }
-                Err(_) => continue,
+            }
+
+            if let Some(next_peer) = queue.pop_front() {
+                let session_key = sessions[&next_peer.id].clone();
+                let connection = connections.get(&next_peer.id).cloned();
+                in_flight.spawn(race_peer(
+                    next_peer,
+                    hash.clone(),
+                    session_key,
+                    my_fingerprint.clone(),
+                    connection,
+                ));
             }
         }
 
-        None
+        // Dropping `in_flight` here aborts whatever's still racing.
+        winner
     }
 }
 
-impl Default for PeerManager {
-    fn default() -> Self {
-        Self::new()
+/// Fetch a peer's `/digest` Bloom filter of the hashes it holds.
+async fn fetch_digest(addr: SocketAddr) -> Result<BloomFilter> {
+    let url = format!("http://{}/digest", addr);
+    let digest = reqwest::get(&url).await?.json::<BloomFilter>().await?;
+    Ok(digest)
+}
+
+/// Fetch a sealed artifact from a peer, decrypt it, and verify its content
+/// hash - the shared core of `fetch_from_peer` and the race in
+/// `fetch_from_network`. Doesn't borrow `PeerManager` so it can be spawned
+/// as an independent task. Goes over `connection` if one is already open
+/// (the common case once a peer has been discovered), falling back to a
+/// one-off HTTP GET otherwise so a fetch still succeeds before the
+/// multiplexed connection finishes dialing.
+async fn fetch_and_verify(
+    peer: &PeerInfo,
+    hash: &str,
+    session_key: &SessionKey,
+    my_fingerprint: &str,
+    connection: Option<Arc<PeerConnection>>,
+) -> Result<FetchOutcome> {
+    let sealed = match connection {
+        Some(conn) => conn
+            .request_artifact(hash, FETCH_TIMEOUT)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Peer {} doesn't have artifact", peer.addr))?,
+        None => {
+            let url = format!("http://{}/artifact/{}", peer.addr, hash);
+            let resp = reqwest::Client::new()
+                .get(&url)
+                .header(PEER_HEADER, my_fingerprint)
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                return Err(anyhow::anyhow!("Peer {} doesn't have artifact", peer.addr));
+            }
+
+            resp.bytes().await?.to_vec()
+        }
+    };
+
+    let data = session_key.decrypt(&sealed)?;
+
+    let actual_hash = blake3::hash(&data).to_hex().to_string();
+    if actual_hash != hash {
+        return Ok(FetchOutcome::HashMismatch);
+    }
+
+    Ok(FetchOutcome::Verified(data))
+}
+
+/// One entry in the `fetch_from_network` race: fetch-and-verify under a
+/// per-request timeout, returning ownership of `peer` and how long it took
+/// so the caller can record latency without looking the peer back up.
+async fn race_peer(
+    peer: PeerInfo,
+    hash: String,
+    session_key: SessionKey,
+    my_fingerprint: String,
+    connection: Option<Arc<PeerConnection>>,
+) -> (PeerInfo, Duration, Result<FetchOutcome>) {
+    let start = Instant::now();
+    let result = tokio::time::timeout(
+        FETCH_TIMEOUT,
+        fetch_and_verify(&peer, &hash, &session_key, &my_fingerprint, connection),
+    )
+    .await;
+
+    let elapsed = start.elapsed();
+    match result {
+        Ok(outcome) => (peer, elapsed, outcome),
+        Err(_) => {
+            let addr = peer.addr;
+            (peer, elapsed, Err(anyhow::anyhow!("Peer {} timed out", addr)))
+        }
     }
 }
 
 /// Shared state for artifact server
 pub struct ArtifactServerState {
     pub cache_db: sled::Db,
+    identity: Arc<Identity>,
+    network_key: NetworkKey,
+    /// Session key for each peer that's completed the handshake, keyed by
+    /// the fingerprint it sends on every subsequent artifact request.
+    sessions: Arc<RwLock<HashMap<String, SessionKey>>>,
 }
 
-/// Start HTTP artifact server
+/// Start HTTP artifact server. `identity` and `network_key` should be the
+/// same ones handed to this daemon's `PeerManager`, so both sides of a
+/// handshake answer for the same keypair and secret.
 pub async fn start_artifact_server(
     cache_db: sled::Db,
+    identity: Arc<Identity>,
+    network_key: NetworkKey,
 ) -> Result<(u16, tokio::task::JoinHandle<()>)> {
-    let state = Arc::new(ArtifactServerState { cache_db });
+    let state = Arc::new(ArtifactServerState {
+        cache_db,
+        identity,
+        network_key,
+        sessions: Arc::new(RwLock::new(HashMap::new())),
+    });
 
     let app = Router::new()
         .route("/artifact/:hash", get(get_artifact))
+        .route("/handshake", post(handshake))
+        .route("/digest", get(get_digest))
         .route("/health", get(health_check))
+        .route("/ws", get(ws_upgrade))
         .with_state(state);
 
     // Bind to random port
@@ -183,49 +706,186 @@ pub async fn start_artifact_server(
     Ok((port, handle))
 }
 
-/// GET /artifact/:hash - Return cached artifact
+/// GET /artifact/:hash - Return cached artifact, sealed under the
+/// requesting peer's session key. Rejects anyone without a session
+/// established via POST /handshake instead of serving plaintext to whoever asks.
 async fn get_artifact(
     State(state): State<Arc<ArtifactServerState>>,
     Path(hash): Path<String>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    let Some(peer_fingerprint) = headers.get(PEER_HEADER).and_then(|v| v.to_str().ok()) else {
+        return (StatusCode::UNAUTHORIZED, vec![]);
+    };
+
+    let Some(session_key) = state.sessions.read().await.get(peer_fingerprint).cloned() else {
+        return (StatusCode::UNAUTHORIZED, vec![]);
+    };
+
     match state.cache_db.get(hash.as_bytes()) {
-        Ok(Some(data)) => (StatusCode::OK, data.to_vec()),
+        Ok(Some(data)) => match session_key.encrypt(&data) {
+            Ok(sealed) => (StatusCode::OK, sealed),
+            Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec![]),
+        },
         Ok(None) => (StatusCode::NOT_FOUND, vec![]),
         Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, vec![]),
     }
 }
 
+/// POST /handshake - Accept a peer's handshake attempt. A proof that doesn't
+/// check out against our network key is rejected before a session is ever
+/// derived, let alone stored.
+async fn handshake(
+    State(state): State<Arc<ArtifactServerState>>,
+    Json(init): Json<HandshakeInit>,
+) -> impl IntoResponse {
+    match respond_to_handshake(&init, &state.identity, &state.network_key) {
+        Ok((peer_fingerprint, session_key, response)) => {
+            state.sessions.write().await.insert(peer_fingerprint, session_key);
+            (StatusCode::OK, Json(Some(response)))
+        }
+        Err(e) => {
+            tracing::warn!("🚫 Rejected handshake: {}", e);
+            (StatusCode::UNAUTHORIZED, Json(None))
+        }
+    }
+}
+
+/// GET /digest - Return a Bloom filter of every hash this peer's cache
+/// holds, so callers can consult `BloomFilter::might_contain` instead of
+/// probing `/artifact/:hash` blind.
+async fn get_digest(State(state): State<Arc<ArtifactServerState>>) -> impl IntoResponse {
+    let keys: Vec<String> = state
+        .cache_db
+        .iter()
+        .keys()
+        .filter_map(|k| k.ok())
+        .map(|k| String::from_utf8_lossy(&k).into_owned())
+        .collect();
+
+    let digest = BloomFilter::from_keys(keys, DIGEST_FALSE_POSITIVE_RATE);
+    Json(digest)
+}
+
 /// GET /health - Simple health check
 async fn health_check() -> &'static str {
     "OK"
 }
 
+/// GET /ws - Upgrade to the multiplexed peer connection, authenticated the
+/// same way `/artifact/:hash` is: the caller must already hold a session
+/// from a completed `/handshake`.
+async fn ws_upgrade(
+    State(state): State<Arc<ArtifactServerState>>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let Some(peer_fingerprint) = headers.get(PEER_HEADER).and_then(|v| v.to_str().ok()) else {
+        return (StatusCode::UNAUTHORIZED, "missing peer fingerprint").into_response();
+    };
+
+    let Some(session_key) = state.sessions.read().await.get(peer_fingerprint).cloned() else {
+        return (StatusCode::UNAUTHORIZED, "no session for peer").into_response();
+    };
+
+    ws.on_upgrade(move |socket| handle_peer_socket(socket, state, session_key)).into_response()
+}
+
+/// Serve one peer's multiplexed connection: artifact requests are answered
+/// from `cache_db` over the requesting stream id, heartbeats are
+/// acknowledged, and announcements are just logged - this server-side half
+/// has no `PeerManager` of its own to update a digest with, that happens on
+/// whichever side dialed out via `PeerConnection::connect`.
+async fn handle_peer_socket(
+    mut socket: WebSocket,
+    state: Arc<ArtifactServerState>,
+    session_key: SessionKey,
+) {
+    while let Some(Ok(msg)) = socket.recv().await {
+        let WsMessage::Text(text) = msg else { continue };
+        let Ok(frame) = serde_json::from_str::<Frame>(&text) else { continue };
+
+        let reply = match frame.payload {
+            FramePayload::ArtifactRequest { hash } => {
+                let sealed = state
+                    .cache_db
+                    .get(hash.as_bytes())
+                    .ok()
+                    .flatten()
+                    .and_then(|data| session_key.encrypt(&data).ok());
+                Some(Frame { stream_id: frame.stream_id, payload: FramePayload::ArtifactResponse { sealed } })
+            }
+            FramePayload::Heartbeat => {
+                Some(Frame { stream_id: frame.stream_id, payload: FramePayload::HeartbeatAck })
+            }
+            FramePayload::Announce { hash } => {
+                tracing::debug!("Peer announced new artifact: {}", hash);
+                None
+            }
+            FramePayload::ArtifactResponse { .. } | FramePayload::HeartbeatAck => None,
+        };
+
+        if let Some(reply) = reply {
+            let Ok(text) = serde_json::to_string(&reply) else { continue };
+            if socket.send(WsMessage::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[tokio::test]
-    async fn test_artifact_server() {
-        // Create temp DB
+    async fn test_artifact_server_requires_handshake() {
         let db = sled::Config::new().temporary(true).open().unwrap();
-
-        // Store test artifact
         db.insert("test-hash", b"Hello P2P!").unwrap();
 
-        // Start server
-        let (port, _handle) = start_artifact_server(db).await.unwrap();
+        let network_key = NetworkKey::from_passphrase("test-network");
+        let server_identity = Arc::new(Identity::generate());
+        let (port, _handle) =
+            start_artifact_server(db, Arc::clone(&server_identity), network_key.clone())
+                .await
+                .unwrap();
 
-        // Fetch artifact
         let url = format!("http://127.0.0.1:{}/artifact/test-hash", port);
-        let resp = reqwest::get(&url).await.unwrap();
 
+        // No handshake yet - server doesn't know us, request is rejected.
+        let resp = reqwest::Client::new()
+            .get(&url)
+            .header(PEER_HEADER, "not-a-real-peer")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        // Handshake, then fetch+decrypt over the resulting session.
+        let addr: SocketAddr = format!("127.0.0.1:{}", port).parse().unwrap();
+        let client_identity = Identity::generate();
+        let (peer_fingerprint, session_key) =
+            initiate_handshake(addr, &client_identity, &network_key)
+                .await
+                .unwrap();
+        assert_eq!(peer_fingerprint, server_identity.fingerprint());
+
+        let resp = reqwest::Client::new()
+            .get(&url)
+            .header(PEER_HEADER, client_identity.fingerprint())
+            .send()
+            .await
+            .unwrap();
         assert!(resp.status().is_success());
-        assert_eq!(resp.text().await.unwrap(), "Hello P2P!");
+
+        let sealed = resp.bytes().await.unwrap().to_vec();
+        let opened = session_key.decrypt(&sealed).unwrap();
+        assert_eq!(opened, b"Hello P2P!");
     }
 
     #[tokio::test]
     async fn test_peer_manager_creation() {
-        let pm = PeerManager::new();
+        let pm = PeerManager::new(NetworkKey::from_passphrase("test-network"));
         assert!(!pm.local_id().is_empty());
 
         let peers = pm.get_peers().await;
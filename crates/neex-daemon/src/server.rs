@@ -4,13 +4,23 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::Path;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tracing::{debug, error, info};
 
 use crate::state::DaemonState;
 use crate::watcher::FileWatcher;
+use neex_core::{Worker, WorkerInfo, WorkerRegistry, WorkerState};
+
+/// How long a presigned artifact URL stays valid for. Short-lived since it's
+/// only meant to bridge the gap between a LAN cache miss and a direct pull
+/// from the cloud bucket, not to be cached and reused later.
+const ARTIFACT_URL_TTL: Duration = Duration::from_secs(300);
 
 /// Request from CLI to daemon
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +37,38 @@ pub enum DaemonRequest {
     Rescan,
     /// Get stats
     Stats,
+    /// Get a presigned URL so a peer that missed the LAN cache can pull an
+    /// artifact straight from the shared cloud bucket
+    ArtifactUrl { hash: String },
+    /// List every registered background worker and its current state
+    ListWorkers,
+    /// Pause a worker by id
+    PauseWorker { id: u64 },
+    /// Resume a paused worker by id
+    ResumeWorker { id: u64 },
+    /// Cancel a worker by id
+    CancelWorker { id: u64 },
+    /// Register a stand-in [`RemoteWorker`] for a job that isn't running in
+    /// this process (e.g. `CloudCache::upload_background`, spawned from the
+    /// CLI) so it's visible in `neex workers` too
+    RegisterWorker { name: String },
+    /// Push a `(state, progress)` report to a previously registered remote worker
+    ReportWorker {
+        id: u64,
+        state: WorkerState,
+        progress: Option<String>,
+    },
+    /// Get the current tranquility level (0-10)
+    GetTranquility,
+    /// Set the tranquility level (0-10, inserts a proportional sleep between
+    /// task dispatches so the daemon yields CPU to interactive work)
+    SetTranquility { level: u8 },
+    /// Every package whose build output could be affected by `name`
+    /// changing, read from the daemon's live dependency graph (see
+    /// `neex_daemon::living_graph`) instead of a one-shot `DepGraph::from_root`
+    GetAffected { name: String },
+    /// Topological build order across the daemon's live dependency graph
+    GetBuildOrder,
     /// Shutdown
     Shutdown,
 }
@@ -38,15 +80,92 @@ pub enum DaemonResponse {
     GlobalHash(String),
     Changed(Vec<String>),
     Stats { cached_files: usize, db_size: u64 },
+    PresignedUrl { url: String, expires_at: u64 },
+    Workers(Vec<WorkerInfo>),
+    WorkerId(u64),
+    Tranquility(u8),
+    Affected(Vec<String>),
+    BuildOrder(Vec<String>),
     Ok,
     Error(String),
 }
 
+/// Stand-in for a job that isn't running in this process - a CLI-triggered
+/// `CloudCache::upload_background`, say - registered on its behalf so it
+/// shows up in `neex workers` anyway. Has no real work to drive itself, so
+/// `pause` and `resume` do nothing and `cancel` only marks it dead; its
+/// state otherwise changes through `report` (called from
+/// `DaemonRequest::ReportWorker`).
+struct RemoteWorker {
+    name: String,
+    state: Mutex<WorkerState>,
+    progress: Mutex<Option<String>>,
+    error_count: AtomicU32,
+}
+
+impl Worker for RemoteWorker {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn state(&self) -> WorkerState {
+        self.state.lock().unwrap().clone()
+    }
+
+    fn progress(&self) -> Option<String> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    fn error_count(&self) -> u32 {
+        self.error_count.load(Ordering::Relaxed)
+    }
+
+    fn pause(&self) {}
+    fn resume(&self) {}
+
+    fn cancel(&self) {
+        *self.state.lock().unwrap() = WorkerState::Dead("cancelled".to_string());
+    }
+
+    fn report(&self, state: WorkerState, progress: Option<String>) {
+        *self.state.lock().unwrap() = state;
+        *self.progress.lock().unwrap() = progress;
+    }
+}
+
+/// A pending response slot for a connection accepted by `poll_once`. Call
+/// `respond` exactly once to write the reply back to the client; dropping it
+/// unanswered just closes the socket.
+pub struct Responder {
+    stream: UnixStream,
+}
+
+impl Responder {
+    /// Send `response` back to the connected client
+    pub fn respond(mut self, response: DaemonResponse) -> Result<()> {
+        let json = serde_json::to_string(&response)?;
+        self.stream.write_all(json.as_bytes())?;
+        self.stream.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
 /// Daemon server
 pub struct DaemonServer {
     socket_path: std::path::PathBuf,
     state: DaemonState,
     watcher: FileWatcher,
+    listener: UnixListener,
+    workers: WorkerRegistry,
+}
+
+impl AsRawFd for DaemonServer {
+    /// Raw fd of the CLI Unix socket listener, so a host process that runs
+    /// its own `select`/`epoll`/`mio` loop can register it alongside its
+    /// other event sources and call `poll_once` only when it's readable.
+    fn as_raw_fd(&self) -> RawFd {
+        self.listener.as_raw_fd()
+    }
 }
 
 impl DaemonServer {
@@ -64,17 +183,56 @@ impl DaemonServer {
         let _ = std::fs::remove_file(&socket_path);
 
         let state = DaemonState::new(root)?;
-        let watcher = FileWatcher::new(root)?;
+        let mut watcher = FileWatcher::new(root)?;
+        watcher.watch_file(neex_core::get_config_path());
+
+        let listener = UnixListener::bind(&socket_path)?;
+        listener.set_nonblocking(true)?;
 
         Ok(Self {
             socket_path,
             state,
             watcher,
+            listener,
+            workers: WorkerRegistry::new(),
         })
     }
 
-    /// Start daemon server
-    pub async fn start(&mut self) -> Result<()> {
+    /// Registry background jobs (cloud uploads, long builds, etc.) register
+    /// themselves with so `neex workers` can observe and control them
+    pub fn workers(&self) -> &WorkerRegistry {
+        &self.workers
+    }
+
+    /// Accept and answer at most one already-ready connection without
+    /// blocking. Returns `Ok(None)` if no connection is waiting, so a host
+    /// process driving its own event loop can call this only when
+    /// `as_raw_fd()` signals readable instead of polling blindly.
+    pub fn poll_once(&self) -> Result<Option<(DaemonRequest, Responder)>> {
+        let (stream, _) = match self.listener.accept() {
+            Ok(pair) => pair,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // The listener is non-blocking so `poll_once` never stalls waiting
+        // for a connection, but once accepted the client writes its request
+        // immediately, so a short blocking read here is fine.
+        stream.set_nonblocking(false)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+
+        let request: DaemonRequest = serde_json::from_str(&line)?;
+        debug!("Request: {:?}", request);
+
+        Ok(Some((request, Responder { stream })))
+    }
+
+    /// Blocking server loop, built as a thin wrapper over `poll_once` for
+    /// callers that don't already run their own reactor. Embedders with an
+    /// existing event loop should drive `poll_once` directly via `as_raw_fd`.
+    pub async fn serve(&mut self) -> Result<()> {
         // Initial scan
         info!("Performing initial file scan...");
         let count = self.state.full_scan()?;
@@ -82,40 +240,40 @@ impl DaemonServer {
 
         // Start file watcher
         self.watcher.start()?;
-
-        // Create Unix socket
-        let listener = UnixListener::bind(&self.socket_path)?;
         info!("Daemon listening on: {:?}", self.socket_path);
 
         loop {
-            tokio::select! {
-                // Handle new connections
-                accept_result = listener.accept() => {
-                    match accept_result {
-                        Ok((stream, _)) => {
-                            if let Err(e) = self.handle_connection(stream).await {
-                                error!("Connection error: {}", e);
-                            }
-                        }
-                        Err(e) => {
-                            error!("Accept error: {}", e);
-                        }
+            match self.poll_once() {
+                Ok(Some((request, responder))) => {
+                    let response = self.handle_request(request);
+                    if let Err(e) = responder.respond(response) {
+                        error!("Failed to write response: {}", e);
                     }
                 }
-
-                // Poll for file changes periodically
-                _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => {
-                    self.process_file_changes();
-                }
+                Ok(None) => {}
+                Err(e) => error!("Accept error: {}", e),
             }
+
+            self.process_file_changes().await;
+            tokio::time::sleep(Duration::from_millis(20)).await;
         }
     }
 
     /// Process pending file changes from watcher
-    fn process_file_changes(&mut self) {
+    async fn process_file_changes(&mut self) {
         let changes = self.watcher.poll();
+        let config_path = neex_core::get_config_path();
+
+        self.state.apply_living_changes(&changes);
 
         for change in changes {
+            if change.path == config_path {
+                if let Err(e) = self.state.reload_cloud_config().await {
+                    error!("Failed to reload cloud config: {}", e);
+                }
+                continue;
+            }
+
             match change.kind {
                 crate::watcher::ChangeKind::Create | crate::watcher::ChangeKind::Modify => {
                     if let Err(e) = self.state.update_file(&change.path) {
@@ -131,18 +289,10 @@ impl DaemonServer {
         }
     }
 
-    /// Handle a single connection
-    async fn handle_connection(&self, mut stream: UnixStream) -> Result<()> {
-        let (reader, mut writer) = stream.split();
-        let mut reader = BufReader::new(reader);
-        let mut line = String::new();
-
-        reader.read_line(&mut line).await?;
-
-        let request: DaemonRequest = serde_json::from_str(&line)?;
-        debug!("Request: {:?}", request);
-
-        let response = match request {
+    /// Decode a request into a response. Pure CPU/state work - no IO - so it
+    /// can be shared between `serve`'s loop and a `poll_once`-driven embedder.
+    fn handle_request(&self, request: DaemonRequest) -> DaemonResponse {
+        match request {
             DaemonRequest::GetHash { path } => {
                 let hash = self.state.get_hash(std::path::Path::new(&path));
                 DaemonResponse::Hash(hash)
@@ -175,16 +325,70 @@ impl DaemonServer {
                 // Would need mutable access, simplified for now
                 DaemonResponse::Ok
             }
+            DaemonRequest::ArtifactUrl { hash } => match self.state.cloud() {
+                Some(cloud) => match cloud.presigned_get_url(&hash, ARTIFACT_URL_TTL) {
+                    Ok(url) => DaemonResponse::PresignedUrl {
+                        url,
+                        expires_at: now_secs() + ARTIFACT_URL_TTL.as_secs(),
+                    },
+                    Err(e) => DaemonResponse::Error(e.to_string()),
+                },
+                None => DaemonResponse::Error("cloud cache is not configured".to_string()),
+            },
+            DaemonRequest::ListWorkers => {
+                self.workers.reap();
+                DaemonResponse::Workers(self.workers.list())
+            }
+            DaemonRequest::PauseWorker { id } => match self.workers.pause(id) {
+                Ok(()) => DaemonResponse::Ok,
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
+            DaemonRequest::ResumeWorker { id } => match self.workers.resume(id) {
+                Ok(()) => DaemonResponse::Ok,
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
+            DaemonRequest::CancelWorker { id } => match self.workers.cancel(id) {
+                Ok(()) => DaemonResponse::Ok,
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
+            DaemonRequest::RegisterWorker { name } => {
+                let worker = Arc::new(RemoteWorker {
+                    name,
+                    state: Mutex::new(WorkerState::Active),
+                    progress: Mutex::new(None),
+                    error_count: AtomicU32::new(0),
+                });
+                DaemonResponse::WorkerId(self.workers.register(worker))
+            }
+            DaemonRequest::ReportWorker { id, state, progress } => {
+                match self.workers.report(id, state, progress) {
+                    Ok(()) => DaemonResponse::Ok,
+                    Err(e) => DaemonResponse::Error(e.to_string()),
+                }
+            }
+            DaemonRequest::GetTranquility => DaemonResponse::Tranquility(self.state.tranquility()),
+            DaemonRequest::SetTranquility { level } => match self.state.set_tranquility(level) {
+                Ok(()) => DaemonResponse::Ok,
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
+            DaemonRequest::GetAffected { name } => {
+                DaemonResponse::Affected(self.state.get_affected(&name))
+            }
+            DaemonRequest::GetBuildOrder => match self.state.get_build_order() {
+                Ok(order) => DaemonResponse::BuildOrder(order),
+                Err(e) => DaemonResponse::Error(e.to_string()),
+            },
             DaemonRequest::Shutdown => {
                 info!("Shutdown requested");
                 std::process::exit(0);
             }
-        };
-
-        let response_json = serde_json::to_string(&response)?;
-        writer.write_all(response_json.as_bytes()).await?;
-        writer.write_all(b"\n").await?;
-
-        Ok(())
+        }
     }
 }
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
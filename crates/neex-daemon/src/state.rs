@@ -1,25 +1,56 @@
 //! Daemon State - RAM-cached file hashes
 //!
-//! Stores file hashes in memory for instant access
+//! Stores file hashes in memory for instant access, indexed by a Merkle tree
+//! (see `neex_core::merkle`) instead of a flat map so the global hash is an
+//! O(1) read of the root and a single file update only touches the O(depth)
+//! nodes on its path, rather than recombining every file's hash on every call.
 //! Persists to sled DB for crash recovery
 
+use crate::jobs::JobStore;
+use crate::living_graph::LivingGraph;
+use crate::watcher::FileChange;
 use anyhow::Result;
-use neex_core::hasher::Hasher;
-use sled::Db;
+use neex_core::{hasher::Hasher, CloudCache, DepGraph, JobState, MerkleTree, ResumeEntry};
+use sled::{Db, Tree};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::Instant;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Sled key the tranquility level is persisted under, in its own
+/// `TRANQUILITY_TREE` so `load_from_db` never mistakes it for a file hash.
+const TRANQUILITY_KEY: &[u8] = b"__tranquility";
+const TRANQUILITY_TREE: &str = "tranquility";
 
 /// Daemon state with cached hashes
 #[allow(dead_code)]
 pub struct DaemonState {
     root: PathBuf,
-    hashes: Arc<RwLock<HashMap<PathBuf, String>>>,
+    hashes: Arc<RwLock<MerkleTree>>,
     db: Db,
+    /// Touched Merkle node hashes, keyed by tree-path (root is `""`). Purely
+    /// a crash-recovery nicety - the tree is always fully rebuildable from
+    /// the leaf hashes already in `db` - so it's written but never read back.
+    merkle_nodes: Tree,
     hasher: Hasher,
     last_scan: Option<Instant>,
+    /// Active cloud cache client, swapped in place on config hot-reload.
+    /// Held behind an `Arc` so an in-flight upload started against the old
+    /// client keeps running against it even after a swap.
+    cloud: Arc<RwLock<Option<Arc<CloudCache>>>>,
+    /// Persisted scheduler job state, so an interrupted run can resume
+    /// instead of restarting from scratch - see `neex_core::resumable`.
+    jobs: JobStore,
+    /// How many proportional milliseconds of sleep a dispatching worker pool
+    /// should insert between task dispatches (0-10), so a background daemon
+    /// can yield CPU to interactive work. Persisted so it survives restarts.
+    tranquility: AtomicU8,
+    /// Live `DepGraph`, kept current as `package.json` files change instead
+    /// of being built once via `DepGraph::from_root` and left stale - see
+    /// `crate::living_graph`.
+    living: LivingGraph,
 }
 
 impl DaemonState {
@@ -34,30 +65,172 @@ impl DaemonState {
         }
 
         let db = sled::open(&db_path)?;
+        let merkle_nodes = db.open_tree("merkle")?;
         let hasher = Hasher::new(&root);
+        let cloud = CloudCache::try_new()?.map(Arc::new);
+        let jobs = JobStore::new(&db)?;
+        let tranquility = db
+            .open_tree(TRANQUILITY_TREE)?
+            .get(TRANQUILITY_KEY)?
+            .and_then(|v| v.first().copied())
+            .unwrap_or(0);
+        let living = LivingGraph::new(DepGraph::from_root(&root)?, root.clone());
 
         Ok(Self {
             root,
-            hashes: Arc::new(RwLock::new(HashMap::new())),
+            hashes: Arc::new(RwLock::new(MerkleTree::new())),
             db,
+            merkle_nodes,
             hasher,
             last_scan: None,
+            cloud: Arc::new(RwLock::new(cloud)),
+            jobs,
+            tranquility: AtomicU8::new(tranquility),
+            living,
         })
     }
 
-    /// Load cached hashes from sled DB
+    /// Current tranquility level (0-10).
+    pub fn tranquility(&self) -> u8 {
+        self.tranquility.load(Ordering::Relaxed)
+    }
+
+    /// Set the tranquility level, clamped to 0-10, and persist it so a
+    /// restarted daemon keeps whatever level was last set.
+    pub fn set_tranquility(&self, level: u8) -> Result<()> {
+        let level = level.min(10);
+        self.tranquility.store(level, Ordering::Relaxed);
+        self.db.open_tree(TRANQUILITY_TREE)?.insert(TRANQUILITY_KEY, &[level])?;
+        Ok(())
+    }
+
+    /// Batch the touched Merkle nodes from an `insert`/`remove` call into the
+    /// dedicated `merkle` tree.
+    fn persist_touched(&self, touched: Vec<(String, Option<String>)>) -> Result<()> {
+        let mut batch = sled::Batch::default();
+        for (key, hash) in touched {
+            match hash {
+                Some(hash) => batch.insert(key.as_bytes(), hash.as_bytes()),
+                None => batch.remove(key.as_bytes()),
+            }
+        }
+        self.merkle_nodes.apply_batch(batch)?;
+        Ok(())
+    }
+
+    /// Persist (or overwrite) a task's job state - call after every status
+    /// transition and whenever a shutdown signal fires.
+    pub fn save_job(&self, job: &JobState) -> Result<()> {
+        self.jobs.save(job)
+    }
+
+    /// Drop a job's persisted record, e.g. once its resume decision has been
+    /// applied and it no longer needs to survive another restart.
+    pub fn remove_job(&self, task_name: &str) -> Result<()> {
+        self.jobs.remove(task_name)
+    }
+
+    /// Load every persisted job and decide what to do with each against the
+    /// workspace's current global hash: unchanged inputs let a finished job
+    /// skip straight to `Completed` and let an in-flight one restart from
+    /// `Pending`; a changed hash always discards the record, since resuming
+    /// against stale inputs would replay outputs that no longer apply.
+    pub fn resume_plan(&self) -> Result<Vec<ResumeEntry>> {
+        let jobs = self.jobs.load_all()?;
+        let current_hash = self.global_hash()?;
+        Ok(neex_core::plan_resume(jobs, &current_hash))
+    }
+
+    /// Fold one batch of watcher changes into the live dependency graph, so
+    /// `get_affected`/`get_build_order` reflect a `package.json` edit without
+    /// a full `DepGraph::from_root` rebuild - see `crate::living_graph`.
+    pub fn apply_living_changes(&self, changes: &[FileChange]) {
+        self.living.apply_changes(changes);
+    }
+
+    /// Every package whose build output could be affected by `name`
+    /// changing, read from the live graph instead of a one-shot snapshot.
+    pub fn get_affected(&self, name: &str) -> Vec<String> {
+        self.living
+            .graph()
+            .read()
+            .unwrap()
+            .get_affected(name)
+            .into_iter()
+            .map(|n| n.name.clone())
+            .collect()
+    }
+
+    /// Topological build order across the live graph.
+    pub fn get_build_order(&self) -> Result<Vec<String>> {
+        Ok(self
+            .living
+            .graph()
+            .read()
+            .unwrap()
+            .get_build_order()?
+            .into_iter()
+            .map(|n| n.name.clone())
+            .collect())
+    }
+
+    /// Current cloud cache client, if one is configured and the last reload
+    /// attempt (if any) passed its `ping()` check
+    pub fn cloud(&self) -> Option<Arc<CloudCache>> {
+        self.cloud.read().unwrap().clone()
+    }
+
+    /// Re-read `~/.neex/config.json` and, if it changed, swap in a new cloud
+    /// cache client. The candidate is validated with `ping()` first so a
+    /// typo'd endpoint or revoked key logs an error and leaves the previous
+    /// (working) client in place instead of silently disabling caching.
+    pub async fn reload_cloud_config(&self) -> Result<()> {
+        let config = neex_core::load_config()?;
+
+        let candidate = match config.s3 {
+            Some(s3) if s3.enabled && !s3.endpoint.is_empty() => CloudCache::from_config(&s3)?,
+            _ => {
+                *self.cloud.write().unwrap() = None;
+                info!("Cloud cache disabled by config reload");
+                return Ok(());
+            }
+        };
+
+        match candidate.ping().await {
+            Ok(true) => {
+                *self.cloud.write().unwrap() = Some(Arc::new(candidate));
+                info!("Cloud cache config reloaded");
+            }
+            Ok(false) => {
+                warn!("New cloud config failed to connect, keeping previous cloud cache");
+            }
+            Err(e) => {
+                warn!("New cloud config failed to connect ({}), keeping previous cloud cache", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebuild the in-memory Merkle tree from the flat leaf hashes persisted
+    /// in sled DB. The tree itself isn't persisted node-by-node for this
+    /// purpose - inserting every leaf here recomputes the same interior
+    /// hashes a prior run had, so there's nothing to gain by reading
+    /// `merkle_nodes` back.
     pub fn load_from_db(&self) -> Result<usize> {
         let mut count = 0;
-        let mut hashes = self.hashes.write().unwrap();
+        let mut tree = MerkleTree::new();
 
         for item in self.db.iter() {
             let (key, value) = item?;
             let path = PathBuf::from(String::from_utf8_lossy(&key).to_string());
             let hash = String::from_utf8_lossy(&value).to_string();
-            hashes.insert(path, hash);
+            tree.insert(&path, hash);
             count += 1;
         }
 
+        *self.hashes.write().unwrap() = tree;
+
         info!("Loaded {} cached hashes from DB", count);
         Ok(count)
     }
@@ -68,18 +241,18 @@ impl DaemonState {
         let files = self.hasher.hash_all()?;
 
         {
-            let mut hashes = self.hashes.write().unwrap();
-            hashes.clear();
+            let mut tree = MerkleTree::new();
 
             // Batch write to DB
             let mut batch = sled::Batch::default();
 
             for file in &files {
-                hashes.insert(file.path.clone(), file.hash.clone());
+                tree.insert(&file.path, file.hash.clone());
                 batch.insert(file.path.to_string_lossy().as_bytes(), file.hash.as_bytes());
             }
 
             self.db.apply_batch(batch)?;
+            *self.hashes.write().unwrap() = tree;
         }
 
         self.last_scan = Some(start);
@@ -89,28 +262,33 @@ impl DaemonState {
         Ok(files.len())
     }
 
-    /// Update hash for a single file
+    /// Update hash for a single file, recomputing only the Merkle nodes on
+    /// its path to the root instead of every file's hash.
     pub fn update_file(&self, path: &Path) -> Result<Option<String>> {
         let hash = self.hasher.hash_file(path)?;
 
-        {
+        let touched = {
             let mut hashes = self.hashes.write().unwrap();
-            hashes.insert(path.to_path_buf(), hash.clone());
-        }
+            hashes.insert(path, hash.clone())
+        };
+        self.persist_touched(touched)?;
 
-        // Persist to DB
+        // Persist the leaf hash itself, so `load_from_db` can rebuild the
+        // tree on restart.
         self.db.insert(path.to_string_lossy().as_bytes(), hash.as_bytes())?;
 
         debug!("Updated hash: {:?}", path);
         Ok(Some(hash))
     }
 
-    /// Remove file from cache
+    /// Remove file from cache, pruning its Merkle leaf and any ancestor left
+    /// with no other children.
     pub fn remove_file(&self, path: &Path) -> Result<()> {
-        {
+        let touched = {
             let mut hashes = self.hashes.write().unwrap();
-            hashes.remove(path);
-        }
+            hashes.remove(path)
+        };
+        self.persist_touched(touched)?;
 
         self.db.remove(path.to_string_lossy().as_bytes())?;
         debug!("Removed: {:?}", path);
@@ -119,22 +297,34 @@ impl DaemonState {
 
     /// Get hash for a file (from RAM cache)
     pub fn get_hash(&self, path: &Path) -> Option<String> {
-        self.hashes.read().unwrap().get(path).cloned()
+        self.hashes.read().unwrap().get(path)
     }
 
-    /// Get global hash (all files combined)
+    /// Get global hash (all files combined) - an O(1) read of the Merkle
+    /// root instead of recombining every file's hash on every call.
     pub fn global_hash(&self) -> Result<String> {
-        self.hasher.global_hash()
+        Ok(self.hashes.read().unwrap().root_hash_or_empty())
     }
 
-    /// Get changed files since provided hashes
+    /// Get files that are new or modified since `old_hashes`, descending
+    /// into the Merkle tree built from it and `self.hashes` together - an
+    /// unchanged directory is skipped as a whole instead of every one of its
+    /// leaves being compared individually.
     pub fn get_changed(&self, old_hashes: &HashMap<PathBuf, String>) -> Vec<PathBuf> {
+        let mut old_tree = MerkleTree::new();
+        for (path, hash) in old_hashes {
+            old_tree.insert(path, hash.clone());
+        }
+
         let current = self.hashes.read().unwrap();
 
+        // `diff` is symmetric (it also surfaces paths removed since
+        // `old_hashes`) - filter those back out so this keeps matching the
+        // "new or modified" contract `Hasher::get_changed` established.
         current
-            .iter()
-            .filter(|(path, hash)| old_hashes.get(*path).map(|h| h != *hash).unwrap_or(true))
-            .map(|(path, _)| path.clone())
+            .diff(&old_tree)
+            .into_iter()
+            .filter(|path| current.get(path).is_some())
             .collect()
     }
 
@@ -142,7 +332,7 @@ impl DaemonState {
     pub fn stats(&self) -> DaemonStats {
         let hashes = self.hashes.read().unwrap();
         DaemonStats {
-            cached_files: hashes.len(),
+            cached_files: hashes.leaves().len(),
             db_size: self.db.size_on_disk().unwrap_or(0),
             last_scan: self.last_scan,
         }
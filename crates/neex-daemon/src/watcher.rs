@@ -2,14 +2,22 @@
 //!
 //! Uses notify crate (FSEvents on macOS, inotify on Linux)
 //! Updates hash cache in real-time
+//!
+//! Pending work is indexed in a radix/patricia trie keyed on path components
+//! (see `PathTrie`) instead of a flat set, so ignored subtrees short-circuit
+//! after their first matching component and a changed file can be attributed
+//! to its owning workspace package in O(depth) instead of a linear scan.
 
 use anyhow::Result;
+use neex_core::hash_ast;
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{channel, Receiver};
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// File change event
@@ -17,6 +25,8 @@ use tracing::{debug, info, warn};
 pub struct FileChange {
     pub path: PathBuf,
     pub kind: ChangeKind,
+    /// Nearest ancestor package root, if the trie has one registered
+    pub package_root: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -26,13 +36,178 @@ pub enum ChangeKind {
     Delete,
 }
 
+/// Per-file state stored at a trie leaf
+#[derive(Debug, Clone, Default)]
+pub struct FileState {
+    /// Last known AST hash for this file, if computed
+    pub hash: Option<String>,
+    pub kind: Option<ChangeKind>,
+}
+
+/// A node in the path trie: one edge per path component
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<OsString, TrieNode>,
+    /// Marks this node as a workspace package root
+    is_package_root: bool,
+    /// Marks this node (and therefore every path beneath it) as ignored
+    is_ignored: bool,
+    file_state: Option<FileState>,
+}
+
+impl TrieNode {
+    fn child_or_insert(&mut self, component: &std::ffi::OsStr) -> &mut TrieNode {
+        self.children.entry(component.to_os_string()).or_default()
+    }
+}
+
+/// Radix-style path index: each edge is a path segment, interior nodes may be
+/// tagged as a package root or an ignored subtree, and leaves carry per-file state.
+#[derive(Debug, Default)]
+pub struct PathTrie {
+    root: TrieNode,
+}
+
+impl PathTrie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert/update the state for `path`
+    pub fn insert(&mut self, path: &Path, state: FileState) {
+        let mut node = &mut self.root;
+        for component in normal_components(path) {
+            node = node.child_or_insert(component);
+        }
+        node.file_state = Some(state);
+    }
+
+    /// Mark `path` as a workspace package root
+    pub fn mark_package_root(&mut self, path: &Path) {
+        let mut node = &mut self.root;
+        for component in normal_components(path) {
+            node = node.child_or_insert(component);
+        }
+        node.is_package_root = true;
+    }
+
+    /// Record a freshly recomputed hash for `path`, leaving any existing
+    /// change-kind on the leaf untouched
+    pub fn update_hash(&mut self, path: &Path, hash: String) {
+        let mut node = &mut self.root;
+        for component in normal_components(path) {
+            node = node.child_or_insert(component);
+        }
+        match &mut node.file_state {
+            Some(state) => state.hash = Some(hash),
+            None => {
+                node.file_state = Some(FileState {
+                    hash: Some(hash),
+                    kind: None,
+                })
+            }
+        }
+    }
+
+    /// Walk from the root consuming components. Returns true (and marks the
+    /// matching node ignored for next time) as soon as a component matches one
+    /// of `patterns`, short-circuiting the rest of the path.
+    pub fn should_ignore(&mut self, path: &Path, patterns: &[String]) -> bool {
+        let mut node = &mut self.root;
+        for component in normal_components(path) {
+            let name = component.to_string_lossy();
+            let entry = node.child_or_insert(component);
+
+            if entry.is_ignored {
+                return true;
+            }
+
+            if patterns.iter().any(|p| name.contains(p.as_str())) {
+                entry.is_ignored = true;
+                return true;
+            }
+
+            node = entry;
+        }
+        false
+    }
+
+    /// Nearest ancestor of `path` (inclusive) tagged as a package root
+    pub fn nearest_package(&self, path: &Path) -> Option<PathBuf> {
+        let mut node = &self.root;
+        let mut acc = PathBuf::new();
+        let mut nearest = None;
+
+        for component in normal_components(path) {
+            let child = node.children.get(component)?;
+            acc.push(component);
+            if child.is_package_root {
+                nearest = Some(acc.clone());
+            }
+            node = child;
+        }
+
+        nearest
+    }
+
+    /// All (path, hash) pairs recorded under `package_root`, for recomputing a
+    /// package's aggregate hash incrementally instead of rehashing the whole tree.
+    pub fn subtree_hashes(&self, package_root: &Path) -> Vec<(PathBuf, String)> {
+        let Some(start) = self.node_at(package_root) else {
+            return vec![];
+        };
+
+        let mut out = Vec::new();
+        collect_hashes(start, package_root, &mut out);
+        out
+    }
+
+    fn node_at(&self, path: &Path) -> Option<&TrieNode> {
+        let mut node = &self.root;
+        for component in normal_components(path) {
+            node = node.children.get(component)?;
+        }
+        Some(node)
+    }
+
+    /// Currently recorded AST hash for `path`, if any - read this before
+    /// `update_hash` overwrites it to tell whether a touch actually changed
+    /// the file's hash.
+    pub fn hash_at(&self, path: &Path) -> Option<String> {
+        self.node_at(path)?.file_state.as_ref()?.hash.clone()
+    }
+}
+
+fn collect_hashes(node: &TrieNode, prefix: &Path, out: &mut Vec<(PathBuf, String)>) {
+    if let Some(state) = &node.file_state {
+        if let Some(hash) = &state.hash {
+            out.push((prefix.to_path_buf(), hash.clone()));
+        }
+    }
+
+    for (name, child) in &node.children {
+        collect_hashes(child, &prefix.join(name), out);
+    }
+}
+
+fn normal_components(path: &Path) -> impl Iterator<Item = &std::ffi::OsStr> {
+    path.components().filter_map(|c| match c {
+        Component::Normal(name) => Some(name),
+        _ => None,
+    })
+}
+
 /// File watcher with debouncing
 pub struct FileWatcher {
     root: PathBuf,
     watcher: Option<RecommendedWatcher>,
     receiver: Option<Receiver<Result<Event, notify::Error>>>,
-    pending_changes: Arc<RwLock<HashSet<PathBuf>>>,
+    pending_changes: Arc<RwLock<std::collections::HashSet<PathBuf>>>,
+    index: Arc<RwLock<PathTrie>>,
     ignore_patterns: Vec<String>,
+    /// Individual files outside `root` (e.g. `~/.neex/config.json`) watched by
+    /// path rather than attributed to the package trie
+    watch_files: Vec<PathBuf>,
 }
 
 impl FileWatcher {
@@ -53,24 +228,42 @@ impl FileWatcher {
             root,
             watcher: None,
             receiver: None,
-            pending_changes: Arc::new(RwLock::new(HashSet::new())),
+            pending_changes: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            index: Arc::new(RwLock::new(PathTrie::new())),
             ignore_patterns,
+            watch_files: Vec::new(),
         })
     }
 
+    /// Register a workspace package root so changes beneath it can be attributed
+    pub fn register_package_root(&self, path: &Path) {
+        self.index.write().unwrap().mark_package_root(path);
+    }
+
+    /// Watch a single file outside `root` (its parent directory is watched
+    /// non-recursively since the file may not exist yet). Must be called
+    /// before `start`. Matching changes are reported via `poll` with
+    /// `package_root: None`, bypassing the ignore-pattern/package trie.
+    pub fn watch_file(&mut self, path: impl Into<PathBuf>) {
+        self.watch_files.push(path.into());
+    }
+
+    /// Get hashes recorded for every file under a package root
+    pub fn subtree_hashes(&self, package_root: &Path) -> Vec<(PathBuf, String)> {
+        self.index.read().unwrap().subtree_hashes(package_root)
+    }
+
+    /// Record a freshly recomputed hash for `path`
+    pub fn update_hash(&self, path: &Path, hash: String) {
+        self.index.write().unwrap().update_hash(path, hash);
+    }
+
     /// Check if path should be ignored
     fn should_ignore(&self, path: &Path) -> bool {
-        for component in path.components() {
-            if let std::path::Component::Normal(name) = component {
-                let name_str = name.to_string_lossy();
-                for pattern in &self.ignore_patterns {
-                    if name_str.contains(pattern) {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        self.index
+            .write()
+            .unwrap()
+            .should_ignore(path, &self.ignore_patterns)
     }
 
     /// Start watching files
@@ -82,6 +275,15 @@ impl FileWatcher {
         let mut watcher = RecommendedWatcher::new(tx, config)?;
         watcher.watch(&self.root, RecursiveMode::Recursive)?;
 
+        for path in &self.watch_files {
+            if let Some(parent) = path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+                if let Err(e) = watcher.watch(parent, RecursiveMode::NonRecursive) {
+                    warn!("Failed to watch {:?}: {}", parent, e);
+                }
+            }
+        }
+
         self.watcher = Some(watcher);
         self.receiver = Some(rx);
 
@@ -106,7 +308,9 @@ impl FileWatcher {
                 match result {
                     Ok(event) => {
                         for path in event.paths {
-                            if self.should_ignore(&path) {
+                            let is_watch_file = self.watch_files.contains(&path);
+
+                            if !is_watch_file && self.should_ignore(&path) {
                                 continue;
                             }
 
@@ -117,8 +321,36 @@ impl FileWatcher {
                                 _ => continue,
                             };
 
+                            if is_watch_file {
+                                debug!("Watched file change: {:?} ({:?})", path, kind);
+                                changes.push(FileChange {
+                                    path,
+                                    kind,
+                                    package_root: None,
+                                });
+                                continue;
+                            }
+
                             debug!("File change: {:?} ({:?})", path, kind);
-                            changes.push(FileChange { path, kind });
+
+                            {
+                                let mut index = self.index.write().unwrap();
+                                index.insert(
+                                    &path,
+                                    FileState {
+                                        hash: None,
+                                        kind: Some(kind),
+                                    },
+                                );
+                            }
+
+                            let package_root = self.index.read().unwrap().nearest_package(&path);
+
+                            changes.push(FileChange {
+                                path,
+                                kind,
+                                package_root,
+                            });
                         }
                     }
                     Err(e) => {
@@ -135,6 +367,89 @@ impl FileWatcher {
     pub fn pending_count(&self) -> usize {
         self.pending_changes.read().unwrap().len()
     }
+
+    /// Block the calling thread, coalescing raw notify events (stored in
+    /// `pending_changes` while a batch is accumulating) into one call to
+    /// `on_batch` per burst of activity: the batch fires once polling has
+    /// come back empty for `debounce`, so a save-then-rename from an editor
+    /// collapses into a single rerun instead of one per filesystem event.
+    /// Each change is AST-rehashed before the callback runs so the trie's
+    /// recorded hashes stay current for `subtree_hashes`, and a non-delete
+    /// change whose AST hash didn't actually move (a whitespace/comment-only
+    /// edit, a save with no real change) is dropped from the batch instead of
+    /// triggering a rerun.
+    pub fn watch_loop(&self, debounce: Duration, on_batch: impl FnMut(Vec<FileChange>)) {
+        let running = AtomicBool::new(true);
+        self.watch_loop_until(debounce, &running, on_batch);
+    }
+
+    /// Same as [`watch_loop`](Self::watch_loop), but checks `running` on every
+    /// tick and returns as soon as it's flipped to `false`, instead of looping
+    /// for the life of the process. Lets a caller running this on a dedicated
+    /// background thread shut it down on demand.
+    pub fn watch_loop_until(
+        &self,
+        debounce: Duration,
+        running: &AtomicBool,
+        mut on_batch: impl FnMut(Vec<FileChange>),
+    ) {
+        let mut batch: HashMap<PathBuf, FileChange> = HashMap::new();
+        let mut quiet_since: Option<Instant> = None;
+
+        while running.load(Ordering::Relaxed) {
+            let drained = self.poll();
+
+            if drained.is_empty() {
+                if let Some(quiet_at) = quiet_since {
+                    if !batch.is_empty() && quiet_at.elapsed() >= debounce {
+                        let changes: Vec<FileChange> = batch.drain().map(|(_, v)| v).collect();
+                        {
+                            let mut pending = self.pending_changes.write().unwrap();
+                            for change in &changes {
+                                pending.remove(&change.path);
+                            }
+                        }
+                        on_batch(changes);
+                        quiet_since = None;
+                    }
+                }
+            } else {
+                {
+                    let mut pending = self.pending_changes.write().unwrap();
+                    for change in &drained {
+                        pending.insert(change.path.clone());
+                    }
+                }
+
+                for change in drained {
+                    let mut unchanged = false;
+
+                    if !matches!(change.kind, ChangeKind::Delete) {
+                        if let Ok(content) = std::fs::read_to_string(&change.path) {
+                            if let Ok(hash) = hash_ast(&change.path, &content) {
+                                let previous = self.index.read().unwrap().hash_at(&change.path);
+                                unchanged = previous.as_deref() == Some(hash.as_str());
+                                self.update_hash(&change.path, hash);
+                            }
+                        }
+                    }
+
+                    if unchanged {
+                        // Not going into `batch`, so it won't be cleared from
+                        // `pending_changes` at fire time like a real change -
+                        // clear it here instead.
+                        self.pending_changes.write().unwrap().remove(&change.path);
+                    } else {
+                        batch.insert(change.path.clone(), change);
+                    }
+                }
+
+                quiet_since = Some(Instant::now());
+            }
+
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
 }
 
 impl Drop for FileWatcher {
@@ -142,3 +457,47 @@ impl Drop for FileWatcher {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_short_circuit() {
+        let mut trie = PathTrie::new();
+        let patterns = vec!["node_modules".to_string()];
+
+        assert!(trie.should_ignore(Path::new("packages/web/node_modules/foo/index.js"), &patterns));
+        // Second lookup hits the cached `is_ignored` node without rescanning patterns
+        assert!(trie.should_ignore(Path::new("packages/web/node_modules/bar/index.js"), &patterns));
+        assert!(!trie.should_ignore(Path::new("packages/web/src/index.ts"), &patterns));
+    }
+
+    #[test]
+    fn test_nearest_package() {
+        let mut trie = PathTrie::new();
+        trie.mark_package_root(Path::new("packages/web"));
+
+        let nearest = trie.nearest_package(Path::new("packages/web/src/index.ts"));
+        assert_eq!(nearest, Some(PathBuf::from("packages/web")));
+
+        assert_eq!(trie.nearest_package(Path::new("packages/other/index.ts")), None);
+    }
+
+    #[test]
+    fn test_subtree_hashes() {
+        let mut trie = PathTrie::new();
+        trie.mark_package_root(Path::new("packages/web"));
+        trie.insert(
+            Path::new("packages/web/src/index.ts"),
+            FileState {
+                hash: Some("abc123".to_string()),
+                kind: Some(ChangeKind::Modify),
+            },
+        );
+
+        let hashes = trie.subtree_hashes(Path::new("packages/web"));
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes[0].1, "abc123");
+    }
+}